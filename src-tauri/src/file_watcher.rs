@@ -1,15 +1,216 @@
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use notify_debouncer_full::{
     new_debouncer,
-    notify::{RecommendedWatcher, RecursiveMode, Watcher},
+    notify::{
+        event::{ModifyKind, RenameMode},
+        EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    },
     DebounceEventResult, Debouncer, FileIdMap,
 };
+use serde::Serialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use crate::search_index::SearchIndex;
 use unicode_normalization::UnicodeNormalization;
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Create,
+    Modify,
+    Remove,
+    Rename,
+}
+
+/// Payload of `file-changed`, and the elements of `workspace-changed`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+/// Payload of `file-renamed`
+#[derive(Debug, Clone, Serialize)]
+pub struct FileRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Payload of `watcher-error`, for a watcher that may have stopped delivering events
+#[derive(Debug, Clone, Serialize)]
+pub struct WatcherError {
+    /// The watched file or workspace path
+    pub path: String,
+    pub message: String,
+}
+
+fn watcher_error(path: &str, errors: &[notify_debouncer_full::notify::Error]) -> WatcherError {
+    let message = errors.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("; ");
+    WatcherError {
+        path: path.to_string(),
+        message,
+    }
+}
+
+/// Map a notify event onto what the UI cares about for `path`, the event's `index`th path;
+/// reads/access are ignored. A rename only counts as `Rename` for the path moved away from: the
+/// destination of an atomic save (temp file renamed over the page) is a `Modify`.
+fn change_kind(kind: &EventKind, index: usize, path: &Path) -> Option<ChangeKind> {
+    match kind {
+        EventKind::Create(_) => Some(ChangeKind::Create),
+        EventKind::Modify(ModifyKind::Name(mode)) => {
+            let is_destination = match mode {
+                RenameMode::To => true,
+                RenameMode::From => false,
+                RenameMode::Both => index == 1,
+                // Direction unknown (e.g. macOS FSEvents): the end that still exists is the destination
+                _ => path.exists(),
+            };
+            if is_destination {
+                Some(ChangeKind::Modify)
+            } else {
+                Some(ChangeKind::Rename)
+            }
+        }
+        EventKind::Modify(_) => Some(ChangeKind::Modify),
+        EventKind::Remove(_) => Some(ChangeKind::Remove),
+        EventKind::Access(_) => None,
+        EventKind::Any | EventKind::Other => Some(ChangeKind::Modify),
+    }
+}
+
+/// Default debounce for a single file: long enough to see an editor's whole atomic save
+const FILE_DEBOUNCE_MS: u64 = 300;
+/// Default debounce for the workspace watcher
+const WORKSPACE_DEBOUNCE_MS: u64 = 200;
+/// Default minimum gap between `workspace-changed` events, so bulk rewrites (formatters,
+/// git checkout) don't make the UI rescan over and over
+const WORKSPACE_MIN_EMIT_INTERVAL_MS: u64 = 500;
+
+/// Paths the workspace watcher skips unless the caller supplies its own globs
+const DEFAULT_IGNORE_GLOBS: &[&str] = &["**/.git/**", "**/node_modules/**"];
+
+/// notify-debouncer-full ticks every `timeout / 4`, so a zero timeout would busy-loop
+fn validate_debounce(debounce_ms: u64) -> Result<Duration, String> {
+    if debounce_ms == 0 {
+        return Err("Debounce must be at least 1 ms".to_string());
+    }
+    Ok(Duration::from_millis(debounce_ms))
+}
+
+fn build_ignore_set(patterns: &[String]) -> Result<GlobSet, String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| format!("Invalid ignore glob '{}': {}", pattern, e))?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|e| format!("Failed to build ignore globs: {}", e))
+}
+
+/// File name in NFC form, so macOS NFD names compare equal to their NFC spelling
+fn nfc_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().nfc().collect())
+        .unwrap_or_default()
+}
+
+/// Rate-limits `workspace-changed`: batches arriving within `min_interval` of the last
+/// emit are merged and sent once, as a single trailing emit when the interval is up
+struct EmitThrottle {
+    min_interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Vec<FileChange>,
+    trailing_scheduled: bool,
+}
+
+impl EmitThrottle {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_emit: None,
+            pending: Vec::new(),
+            trailing_scheduled: false,
+        }
+    }
+
+    /// Add changes, keeping only the latest kind per path
+    fn merge(&mut self, changes: Vec<FileChange>) {
+        for change in changes {
+            match self.pending.iter_mut().find(|pending| pending.path == change.path) {
+                Some(existing) => existing.kind = change.kind,
+                None => self.pending.push(change),
+            }
+        }
+    }
+
+    /// Merge `changes` and decide what to do with them at `now`
+    fn offer(&mut self, changes: Vec<FileChange>, now: Instant) -> ThrottleDecision {
+        self.merge(changes);
+        if self.trailing_scheduled {
+            return ThrottleDecision::Merged;
+        }
+
+        let wait = self
+            .last_emit
+            .map(|last| self.min_interval.saturating_sub(now.saturating_duration_since(last)))
+            .unwrap_or_default();
+        if wait.is_zero() {
+            self.last_emit = Some(now);
+            return ThrottleDecision::EmitNow(std::mem::take(&mut self.pending));
+        }
+
+        self.trailing_scheduled = true;
+        ThrottleDecision::ScheduleTrailing(wait)
+    }
+
+    /// Everything merged since the trailing emit was scheduled, sent at `now`
+    fn take_trailing(&mut self, now: Instant) -> Vec<FileChange> {
+        self.last_emit = Some(now);
+        self.trailing_scheduled = false;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ThrottleDecision {
+    /// Emit this payload immediately
+    EmitNow(Vec<FileChange>),
+    /// Emit the pending changes once this much time has passed
+    ScheduleTrailing(Duration),
+    /// Folded into the trailing emit that is already scheduled
+    Merged,
+}
+
+/// Emit `changes` now if the throttle allows, otherwise fold them into one trailing emit
+fn emit_throttled(app: &AppHandle, throttle: &Arc<Mutex<EmitThrottle>>, changes: Vec<FileChange>) {
+    let decision = throttle.lock().unwrap().offer(changes, Instant::now());
+    match decision {
+        ThrottleDecision::EmitNow(payload) => {
+            let _ = app.emit("workspace-changed", payload);
+        }
+        ThrottleDecision::ScheduleTrailing(wait) => {
+            let app = app.clone();
+            let throttle = throttle.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(wait);
+                let payload = throttle.lock().unwrap().take_trailing(Instant::now());
+                if !payload.is_empty() {
+                    let _ = app.emit("workspace-changed", payload);
+                }
+            });
+        }
+        ThrottleDecision::Merged => {}
+    }
+}
+
+/// Whether the path lies inside a `.git` directory, whose churn is never note content
+fn is_in_git_dir(path: &Path) -> bool {
+    path.components().any(|component| component == Component::Normal(".git".as_ref()))
+}
+
 pub struct FileWatcherManager {
     watchers: Arc<Mutex<HashMap<String, Debouncer<RecommendedWatcher, FileIdMap>>>>,
 }
@@ -21,7 +222,8 @@ impl FileWatcherManager {
         }
     }
 
-    pub fn watch_file(&self, app: AppHandle, file_path: String) -> Result<(), String> {
+    pub fn watch_file(&self, app: AppHandle, file_path: String, debounce_ms: u64) -> Result<(), String> {
+        let debounce = validate_debounce(debounce_ms)?;
         let path = PathBuf::from(&file_path);
 
         // Get parent directory to watch (for vi/vim compatibility)
@@ -44,23 +246,45 @@ impl FileWatcherManager {
         let file_path_clone = file_path.clone();
         let file_name_clone = file_name.clone();
 
-        // Create debounced watcher (waits 300ms after last event for better editor compatibility)
+        // Create debounced watcher (waits after the last event for better editor compatibility)
         let mut debouncer = new_debouncer(
-            Duration::from_millis(300),
+            debounce,
             None,
             move |result: DebounceEventResult| {
                 match result {
                     Ok(events) => {
-                        let mut should_emit = false;
+                        // Kind of the last event in this batch that touched our file
+                        let mut latest_kind: Option<ChangeKind> = None;
+                        // Set when our file itself was renamed away (e.g. in Finder)
+                        let mut renamed_to: Option<PathBuf> = None;
+                        // Set when our file was written again after being renamed away, as vim
+                        // does by moving the page to a backup and writing a fresh copy
+                        let mut recreated = false;
 
                         for event in events {
+                            // A rename with both ends known: moving our file away is a rename,
+                            // while moving a temp file onto it is an atomic save (handled below)
+                            if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind {
+                                if let [from, to] = event.paths.as_slice() {
+                                    let target: String = file_name_clone.nfc().collect();
+                                    if nfc_file_name(from) == target && nfc_file_name(to) != target {
+                                        renamed_to = Some(to.clone());
+                                        continue;
+                                    }
+                                }
+                            }
+
                             // For VSCode/TextEdit atomic saves, we need to check if ANY event
                             // in this batch affects our target file. This includes:
                             // 1. Direct modifications (vi/vim style)
                             // 2. Rename events where destination is our file (VSCode/TextEdit style)
                             // 3. Create events for our file
+                            for (index, event_path) in event.paths.iter().enumerate() {
+                                let kind = match change_kind(&event.kind, index, event_path) {
+                                    Some(kind) => kind,
+                                    None => break,
+                                };
 
-                            for event_path in &event.paths {
                                 // Get the full path as string for comparison
                                 let event_path_str = event_path.to_string_lossy();
 
@@ -70,32 +294,47 @@ impl FileWatcherManager {
 
                                 // Check if the event path ends with our target filename
                                 // This catches both direct edits and atomic save renames
-                                if normalized_path.ends_with(&normalized_target) {
-                                    should_emit = true;
-                                    break;
-                                }
-
-                                // Also check just the filename for safety
-                                if let Some(name) = event_path.file_name() {
-                                    let event_filename = name.to_string_lossy();
-                                    let normalized_event: String = event_filename.nfc().collect();
-                                    if normalized_event == normalized_target {
-                                        should_emit = true;
-                                        break;
+                                let matches_target = normalized_path.ends_with(&normalized_target)
+                                    // Also check just the filename for safety
+                                    || event_path
+                                        .file_name()
+                                        .map(|name| name.to_string_lossy().nfc().collect::<String>() == normalized_target)
+                                        .unwrap_or(false);
+
+                                if matches_target {
+                                    if renamed_to.is_some() && matches!(kind, ChangeKind::Create | ChangeKind::Modify) {
+                                        recreated = true;
                                     }
+                                    latest_kind = Some(kind);
+                                    break;
                                 }
                             }
+                        }
 
-                            if should_emit {
-                                break;
-                            }
+                        // Only a rename if the page is really gone; if it was written again
+                        // (vim's backup-then-write save), the open page just needs a reload
+                        if renamed_to.is_some() && (recreated || Path::new(&file_path_clone).exists()) {
+                            renamed_to = None;
+                            latest_kind = Some(ChangeKind::Modify);
                         }
 
-                        if should_emit {
-                            let _ = app_clone.emit("file-changed", file_path_clone.clone());
+                        if let Some(to) = renamed_to {
+                            let rename = FileRename {
+                                from: file_path_clone.clone(),
+                                to: to.to_string_lossy().to_string(),
+                            };
+                            let _ = app_clone.emit("file-renamed", rename);
+                        } else if let Some(kind) = latest_kind {
+                            let change = FileChange {
+                                path: file_path_clone.clone(),
+                                kind,
+                            };
+                            let _ = app_clone.emit("file-changed", change);
                         }
                     }
-                    Err(_) => {}
+                    Err(errors) => {
+                        let _ = app_clone.emit("watcher-error", watcher_error(&file_path_clone, &errors));
+                    }
                 }
             },
         )
@@ -114,8 +353,18 @@ impl FileWatcherManager {
         Ok(())
     }
 
-    pub fn watch_workspace(&self, app: AppHandle, workspace_path: String) -> Result<(), String> {
+    pub fn watch_workspace(
+        &self,
+        app: AppHandle,
+        workspace_path: String,
+        recursive: bool,
+        debounce_ms: u64,
+        ignore_globs: &[String],
+        min_emit_interval_ms: u64,
+    ) -> Result<(), String> {
+        let debounce = validate_debounce(debounce_ms)?;
         let path = PathBuf::from(&workspace_path);
+        let ignore_set = build_ignore_set(ignore_globs)?;
 
         // Check if already watching
         let mut watchers = self.watchers.lock().unwrap();
@@ -125,44 +374,83 @@ impl FileWatcherManager {
         }
 
         let app_clone = app.clone();
+        let workspace_root = path.clone();
+        let workspace_path_clone = workspace_path.clone();
+        let mut unavailable_reported = false;
+        let throttle = Arc::new(Mutex::new(EmitThrottle::new(Duration::from_millis(min_emit_interval_ms))));
 
-        // Create debounced watcher (waits 200ms after last event for workspace)
+        // Create debounced watcher (waits after the last event for workspace)
         let mut debouncer = new_debouncer(
-            Duration::from_millis(200),
+            debounce,
             None,
             move |result: DebounceEventResult| {
+                // A moved/deleted workspace shows up as events (or errors) on the root itself
+                if !workspace_root.is_dir() {
+                    if !unavailable_reported {
+                        unavailable_reported = true;
+                        let _ = app_clone.emit("workspace-unavailable", workspace_path_clone.clone());
+                    }
+                    return;
+                }
+                unavailable_reported = false;
+
                 match result {
                     Ok(events) => {
-                        let mut has_changes = false;
+                        // Collect any .md file changes, keeping the latest kind per path
+                        let mut changes: Vec<(PathBuf, ChangeKind)> = Vec::new();
                         for event in events {
-                            // Check for any .md file changes (create, remove, modify)
-                            for path in &event.paths {
-                                if let Some(ext) = path.extension() {
-                                    if ext == "md" {
-                                        has_changes = true;
-                                        break;
-                                    }
+                            for (index, path) in event.paths.iter().enumerate() {
+                                let kind = match change_kind(&event.kind, index, path) {
+                                    Some(kind) => kind,
+                                    None => break,
+                                };
+                                if is_in_git_dir(path) || ignore_set.is_match(path) {
+                                    continue;
+                                }
+                                if path.extension().map(|ext| ext != "md").unwrap_or(true) {
+                                    continue;
+                                }
+                                match changes.iter_mut().find(|(changed, _)| changed == path) {
+                                    Some(existing) => existing.1 = kind,
+                                    None => changes.push((path.clone(), kind)),
                                 }
-                            }
-                            if has_changes {
-                                break;
                             }
                         }
 
-                        if has_changes {
-                            let _ = app_clone.emit("workspace-changed", ());
+                        if !changes.is_empty() {
+                            let changed_paths: Vec<PathBuf> = changes.iter().map(|(path, _)| path.clone()).collect();
+                            // Keep the search index in sync before notifying the UI
+                            if let Some(search_index) = app_clone.try_state::<SearchIndex>() {
+                                search_index.refresh_paths(&changed_paths);
+                            }
+                            // Send the affected paths so the UI can refresh just those cards
+                            let payload: Vec<FileChange> = changes
+                                .into_iter()
+                                .map(|(path, kind)| FileChange {
+                                    path: path.to_string_lossy().to_string(),
+                                    kind,
+                                })
+                                .collect();
+                            emit_throttled(&app_clone, &throttle, payload);
                         }
                     }
-                    Err(_) => {}
+                    Err(errors) => {
+                        let _ = app_clone.emit("watcher-error", watcher_error(&workspace_path_clone, &errors));
+                    }
                 }
             },
         )
         .map_err(|e| format!("Failed to create workspace watcher: {}", e))?;
 
-        // Watch the workspace directory (non-recursive, only top level)
+        // Watch the workspace directory (top level only, unless nested folders were requested)
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
         debouncer
             .watcher()
-            .watch(&path, RecursiveMode::NonRecursive)
+            .watch(&path, mode)
             .map_err(|e| format!("Failed to watch workspace: {}", e))?;
 
         // Store the debouncer
@@ -181,6 +469,24 @@ impl FileWatcherManager {
         }
     }
 
+    pub fn unwatch_workspace(&self, workspace_path: String) -> Result<(), String> {
+        let mut watchers = self.watchers.lock().unwrap();
+
+        if watchers.remove(&format!("workspace:{}", workspace_path)).is_some() {
+            Ok(())
+        } else {
+            Err("Workspace was not being watched".to_string())
+        }
+    }
+
+    /// Keys of every active watcher: file paths and `workspace:<path>` entries
+    pub fn list_watched(&self) -> Vec<String> {
+        let watchers = self.watchers.lock().unwrap();
+        let mut keys: Vec<String> = watchers.keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
     pub fn unwatch_all(&self) {
         let mut watchers = self.watchers.lock().unwrap();
         watchers.clear();
@@ -192,8 +498,9 @@ pub async fn watch_file(
     app: AppHandle,
     file_watcher: tauri::State<'_, FileWatcherManager>,
     file_path: String,
+    debounce_ms: Option<u64>,
 ) -> Result<(), String> {
-    file_watcher.watch_file(app, file_path)
+    file_watcher.watch_file(app, file_path, debounce_ms.unwrap_or(FILE_DEBOUNCE_MS))
 }
 
 #[tauri::command]
@@ -209,6 +516,143 @@ pub async fn watch_workspace(
     app: AppHandle,
     file_watcher: tauri::State<'_, FileWatcherManager>,
     workspace_path: String,
+    recursive: Option<bool>,
+    debounce_ms: Option<u64>,
+    ignore_globs: Option<Vec<String>>,
+    min_emit_interval_ms: Option<u64>,
+) -> Result<(), String> {
+    let ignore_globs = ignore_globs
+        .unwrap_or_else(|| DEFAULT_IGNORE_GLOBS.iter().map(|glob| glob.to_string()).collect());
+    file_watcher.watch_workspace(
+        app,
+        workspace_path,
+        recursive.unwrap_or(false),
+        debounce_ms.unwrap_or(WORKSPACE_DEBOUNCE_MS),
+        &ignore_globs,
+        min_emit_interval_ms.unwrap_or(WORKSPACE_MIN_EMIT_INTERVAL_MS),
+    )
+}
+
+#[tauri::command]
+pub async fn unwatch_workspace(
+    file_watcher: tauri::State<'_, FileWatcherManager>,
+    workspace_path: String,
 ) -> Result<(), String> {
-    file_watcher.watch_workspace(app, workspace_path)
+    file_watcher.unwatch_workspace(workspace_path)
+}
+
+#[tauri::command]
+pub async fn list_watched(file_watcher: tauri::State<'_, FileWatcherManager>) -> Result<Vec<String>, String> {
+    Ok(file_watcher.list_watched())
+}
+
+#[tauri::command]
+pub async fn workspace_exists(path: String) -> Result<bool, String> {
+    Ok(PathBuf::from(&path).is_dir())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify_debouncer_full::notify::event::{AccessKind, CreateKind, DataChange, RemoveKind};
+
+    fn change(path: &str, kind: ChangeKind) -> FileChange {
+        FileChange {
+            path: path.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn change_kind_maps_events() {
+        let path = Path::new("/nonexistent/my-kanban/page.md");
+        assert_eq!(change_kind(&EventKind::Create(CreateKind::File), 0, path), Some(ChangeKind::Create));
+        assert_eq!(
+            change_kind(&EventKind::Modify(ModifyKind::Data(DataChange::Content)), 0, path),
+            Some(ChangeKind::Modify)
+        );
+        assert_eq!(change_kind(&EventKind::Remove(RemoveKind::File), 0, path), Some(ChangeKind::Remove));
+        assert_eq!(change_kind(&EventKind::Access(AccessKind::Read), 0, path), None);
+        assert_eq!(change_kind(&EventKind::Any, 0, path), Some(ChangeKind::Modify));
+    }
+
+    #[test]
+    fn change_kind_treats_rename_destination_as_modify() {
+        let missing = Path::new("/nonexistent/my-kanban/page.md");
+        let rename = |mode| EventKind::Modify(ModifyKind::Name(mode));
+
+        assert_eq!(change_kind(&rename(RenameMode::From), 0, missing), Some(ChangeKind::Rename));
+        assert_eq!(change_kind(&rename(RenameMode::To), 0, missing), Some(ChangeKind::Modify));
+        assert_eq!(change_kind(&rename(RenameMode::Both), 0, missing), Some(ChangeKind::Rename));
+        assert_eq!(change_kind(&rename(RenameMode::Both), 1, missing), Some(ChangeKind::Modify));
+        // Unknown direction: decided by whether the path still exists
+        assert_eq!(change_kind(&rename(RenameMode::Any), 0, missing), Some(ChangeKind::Rename));
+        assert_eq!(
+            change_kind(&rename(RenameMode::Any), 0, &std::env::temp_dir()),
+            Some(ChangeKind::Modify)
+        );
+    }
+
+    #[test]
+    fn throttle_merge_keeps_latest_kind_per_path() {
+        let mut throttle = EmitThrottle::new(Duration::from_millis(500));
+        throttle.merge(vec![change("a.md", ChangeKind::Create), change("b.md", ChangeKind::Modify)]);
+        throttle.merge(vec![change("a.md", ChangeKind::Modify), change("c.md", ChangeKind::Remove)]);
+
+        assert_eq!(
+            throttle.pending,
+            vec![
+                change("a.md", ChangeKind::Modify),
+                change("b.md", ChangeKind::Modify),
+                change("c.md", ChangeKind::Remove),
+            ]
+        );
+    }
+
+    #[test]
+    fn throttle_emits_first_batch_and_merges_the_rest_into_one_trailing_emit() {
+        let interval = Duration::from_millis(500);
+        let mut throttle = EmitThrottle::new(interval);
+        let start = Instant::now();
+
+        assert_eq!(
+            throttle.offer(vec![change("a.md", ChangeKind::Modify)], start),
+            ThrottleDecision::EmitNow(vec![change("a.md", ChangeKind::Modify)])
+        );
+
+        // Within the window: one trailing emit for the remaining time, later batches fold into it
+        let early = start + Duration::from_millis(100);
+        assert_eq!(
+            throttle.offer(vec![change("b.md", ChangeKind::Create)], early),
+            ThrottleDecision::ScheduleTrailing(Duration::from_millis(400))
+        );
+        assert_eq!(
+            throttle.offer(
+                vec![change("b.md", ChangeKind::Modify), change("c.md", ChangeKind::Remove)],
+                start + Duration::from_millis(200)
+            ),
+            ThrottleDecision::Merged
+        );
+
+        let trailing_at = early + Duration::from_millis(400);
+        assert_eq!(
+            throttle.take_trailing(trailing_at),
+            vec![change("b.md", ChangeKind::Modify), change("c.md", ChangeKind::Remove)]
+        );
+        assert!(!throttle.trailing_scheduled);
+
+        // The trailing emit restarts the window
+        assert_eq!(
+            throttle.offer(vec![change("d.md", ChangeKind::Modify)], trailing_at + Duration::from_millis(10)),
+            ThrottleDecision::ScheduleTrailing(Duration::from_millis(490))
+        );
+        assert_eq!(
+            throttle.take_trailing(trailing_at + interval),
+            vec![change("d.md", ChangeKind::Modify)]
+        );
+        assert_eq!(
+            throttle.offer(vec![change("e.md", ChangeKind::Modify)], trailing_at + interval * 2),
+            ThrottleDecision::EmitNow(vec![change("e.md", ChangeKind::Modify)])
+        );
+    }
 }
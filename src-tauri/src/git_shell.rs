@@ -1,5 +1,12 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use std::time::Duration;
+use tauri::{AppHandle, Emitter};
 use wait_timeout::ChildExt;
 use serde::{Deserialize, Serialize};
 
@@ -8,6 +15,236 @@ pub struct GitCommitOptions {
     pub message: String,
     pub author_name: String,
     pub author_email: String,
+    /// Permit committing with an empty or whitespace-only message
+    #[serde(default)]
+    pub allow_empty_message: bool,
+    /// Rewrite HEAD instead of creating a new commit; allowed with no new changes
+    #[serde(default)]
+    pub amend: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitCommitResult {
+    pub hash: String,
+    /// Non-fatal problem with the commit, e.g. a very short message
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitLogEntry {
+    pub hash: String,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author date as a Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub subject: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitDetail {
+    pub hash: String,
+    pub author_name: String,
+    /// Author date as a Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub subject: String,
+    pub body: String,
+    /// Patch introduced by the commit (a combined diff for merges)
+    pub diff: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlameLine {
+    /// 1-based line number in the current file
+    pub line_number: usize,
+    pub content: String,
+    pub author: String,
+    /// Author date as a Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub commit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LfsMigrateResult {
+    /// Output of `git lfs migrate`, e.g. the rewritten refs and converted objects
+    pub summary: String,
+    pub warning: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitEnvironment {
+    /// `None` when git isn't installed
+    pub git_version: Option<String>,
+    pub lfs_available: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BranchInfo {
+    /// Local branch name, or `remote/branch` for remote-tracking branches
+    pub name: String,
+    pub is_current: bool,
+    pub is_remote: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResetMode {
+    /// Move HEAD only; changes stay staged
+    Soft,
+    /// Move HEAD and reset the index; changes stay in the working tree
+    Mixed,
+    /// Move HEAD and discard all changes
+    Hard,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RemoteInfo {
+    pub name: String,
+    pub fetch_url: Option<String>,
+    /// Differs from `fetch_url` only when a separate pushurl is configured
+    pub push_url: Option<String>,
+}
+
+/// `--pretty` format matching `parse_log_entries`: hash, author name/email, time, subject
+const LOG_ENTRY_FORMAT: &str = "--pretty=format:%H%x1f%an%x1f%ae%x1f%at%x1f%s";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorktreeInfo {
+    pub path: String,
+    pub head: String,
+    /// Short branch name, `None` when the worktree is detached
+    pub branch: Option<String>,
+    pub is_detached: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RemoteState {
+    pub workspace_path: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+struct RemotePoller {
+    stop: Arc<AtomicBool>,
+    interval_secs: u64,
+}
+
+/// Background `git fetch` pollers, keyed by workspace path
+pub struct RemotePollManager {
+    pollers: Arc<Mutex<HashMap<String, RemotePoller>>>,
+}
+
+impl RemotePollManager {
+    pub fn new() -> Self {
+        Self {
+            pollers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Start polling a workspace. If it's already polled at a different interval, the
+    /// old poller is stopped and replaced.
+    pub fn start(&self, app: AppHandle, workspace_path: String, interval_secs: u64) -> Result<(), String> {
+        if interval_secs == 0 {
+            return Err("Poll interval must be at least 1 second".to_string());
+        }
+
+        let mut pollers = self.pollers.lock().unwrap();
+        if let Some(existing) = pollers.get(&workspace_path) {
+            if existing.interval_secs == interval_secs {
+                return Ok(()); // Already polling
+            }
+            existing.stop.store(true, Ordering::Relaxed);
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        pollers.insert(
+            workspace_path.clone(),
+            RemotePoller {
+                stop: stop.clone(),
+                interval_secs,
+            },
+        );
+
+        std::thread::spawn(move || {
+            let mut last_state: Option<(usize, usize)> = None;
+
+            while !stop.load(Ordering::Relaxed) {
+                // Quiet fetch; failures (offline, no remote) just skip this round
+                if run_git_command(&workspace_path, &["fetch", "--quiet"], 30).is_ok() {
+                    let state = get_ahead_behind(&workspace_path);
+                    if last_state != Some(state) {
+                        last_state = Some(state);
+                        let _ = app.emit(
+                            "remote-state-changed",
+                            RemoteState {
+                                workspace_path: workspace_path.clone(),
+                                ahead: state.0,
+                                behind: state.1,
+                            },
+                        );
+                    }
+                }
+
+                // Sleep in short steps so stop requests are honoured promptly
+                for _ in 0..interval_secs * 4 {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    std::thread::sleep(Duration::from_millis(250));
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    pub fn stop(&self, workspace_path: &str) -> Result<(), String> {
+        let mut pollers = self.pollers.lock().unwrap();
+        match pollers.remove(workspace_path) {
+            Some(poller) => {
+                poller.stop.store(true, Ordering::Relaxed);
+                Ok(())
+            }
+            None => Err("Workspace was not being polled".to_string()),
+        }
+    }
+
+    /// Stop every poller, e.g. when the app is shutting down
+    pub fn stop_all(&self) {
+        let mut pollers = self.pollers.lock().unwrap();
+        for (_, poller) in pollers.drain() {
+            poller.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Messages shorter than this are accepted but come back with a warning
+const SHORT_COMMIT_MESSAGE_LEN: usize = 8;
+
+/// Reject empty commit messages unless explicitly allowed, returning a warning for the
+/// UI when the message is very short.
+/// Errors are prefixed with `EMPTY_COMMIT_MESSAGE:` so the UI can recognise them.
+fn validate_commit_message(message: &str, allow_empty_message: bool) -> Result<Option<String>, String> {
+    let message = message.trim();
+
+    if message.is_empty() {
+        if allow_empty_message {
+            return Ok(None);
+        }
+        return Err("EMPTY_COMMIT_MESSAGE: Commit message cannot be empty".to_string());
+    }
+
+    if message.chars().count() < SHORT_COMMIT_MESSAGE_LEN {
+        return Ok(Some(format!("Very short commit message: {:?}", message)));
+    }
+
+    Ok(None)
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -21,45 +258,308 @@ pub struct GitConfig {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GitStatus {
+    /// Branch name, or `(detached: <short hash>)` when HEAD is detached
     pub branch: String,
+    pub is_detached: bool,
     pub modified_count: usize,
     pub staged_count: usize,
     pub ahead: usize,
     pub behind: usize,
     pub is_clean: bool,
+    /// Changed paths; for renames/copies this is the destination path
     pub modified_files: Vec<String>,
+    pub renamed_files: Vec<GitRename>,
+    /// `None` in a repository without commits
+    pub last_commit: Option<LastCommit>,
+    /// Unmerged paths are present (a merge, rebase or stash pop stopped on conflicts)
+    pub has_conflicts: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LastCommit {
+    pub hash: String,
+    pub subject: String,
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// One entry of `git status --porcelain -z`
+#[derive(Debug, Clone)]
+pub(crate) struct PorcelainEntry {
+    /// Index (staged) status code
+    pub index: char,
+    /// Working tree (unstaged) status code
+    pub worktree: char,
+    pub path: String,
+    /// Source path for renames/copies
+    pub orig_path: Option<String>,
+}
+
+impl PorcelainEntry {
+    /// Unmerged states: both sides touched the path (DD, AU, UD, UA, DU, AA, UU)
+    fn is_unmerged(&self) -> bool {
+        matches!(
+            (self.index, self.worktree),
+            ('D', 'D') | ('A', 'A') | ('U', _) | (_, 'U')
+        )
+    }
+}
+
+/// Parse NUL-terminated porcelain v1 output. With `-z` paths are never quoted or
+/// escaped, and a rename/copy is followed by a separate field holding its source path.
+pub(crate) fn parse_porcelain_z(output: &str) -> Vec<PorcelainEntry> {
+    let mut entries = Vec::new();
+    let mut fields = output.split('\0');
+
+    while let Some(field) = fields.next() {
+        let mut chars = field.chars();
+        let (index, worktree) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(index), Some(worktree), Some(' ')) => (index, worktree),
+            _ => continue,
+        };
+        let path = field[3..].to_string();
+
+        let orig_path = if index == 'R' || index == 'C' {
+            fields.next().map(|orig| orig.to_string())
+        } else {
+            None
+        };
+
+        entries.push(PorcelainEntry {
+            index,
+            worktree,
+            path,
+            orig_path,
+        });
+    }
+
+    entries
+}
+
+/// The parts of `GitStatus` that come from `git status --porcelain -z`
+struct PorcelainSummary {
+    modified_count: usize,
+    staged_count: usize,
+    modified_files: Vec<String>,
+    renamed_files: Vec<GitRename>,
+    has_conflicts: bool,
+}
+
+fn summarize_porcelain(output: &str) -> PorcelainSummary {
+    let mut summary = PorcelainSummary {
+        modified_count: 0,
+        staged_count: 0,
+        modified_files: Vec::new(),
+        renamed_files: Vec::new(),
+        has_conflicts: false,
+    };
+
+    for entry in parse_porcelain_z(output) {
+        summary.has_conflicts |= entry.is_unmerged();
+
+        // Check if staged
+        if entry.index != ' ' && entry.index != '?' {
+            summary.staged_count += 1;
+        }
+
+        // Check if modified (unstaged)
+        if entry.worktree != ' ' {
+            summary.modified_count += 1;
+        }
+
+        if let Some(from) = entry.orig_path {
+            summary.renamed_files.push(GitRename {
+                from,
+                to: entry.path.clone(),
+            });
+        }
+        summary.modified_files.push(entry.path);
+    }
+
+    summary
+}
+
+/// Default timeout for commands that talk to a remote (push, pull)
+const NETWORK_TIMEOUT_SECS: u64 = 30;
+
+/// History rewrites touch every commit, so give them as long as a clone
+const LFS_MIGRATE_TIMEOUT_SECS: u64 = 600;
+
+/// Larger files are refused by `git_blame`, which would otherwise stall the UI
+const BLAME_MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Clones download the whole history, so they get far longer than other network commands
+const CLONE_TIMEOUT_SECS: u64 = 600;
+
+/// Execute a git command with timeout; a `timeout_secs` of 0 means no timeout
+pub(crate) fn run_git_command(workspace_path: &str, args: &[&str], timeout_secs: u64) -> Result<String, String> {
+    run_git_command_with_codes(workspace_path, args, timeout_secs, &[0])
+}
+
+/// Read a child pipe to the end on its own thread, so large outputs can't fill the
+/// pipe buffer and stall git while we wait for it to exit
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// Where to stream a long-running command's stderr progress
+struct ProgressSink {
+    app: AppHandle,
+    operation: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GitProgress {
+    /// Which operation is reporting: "push", "pull", "clone" or "lfs-migrate"
+    pub operation: String,
+    pub message: String,
+}
+
+/// Like `read_pipe`, but also emits each progress line as a `git-progress` event.
+/// Git redraws progress in place with `\r`, so both `\r` and `\n` end a line.
+fn read_progress_pipe<R: Read + Send + 'static>(pipe: Option<R>, sink: ProgressSink) -> JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut all = Vec::new();
+        let mut line = Vec::new();
+        let mut buf = [0u8; 1024];
+
+        let mut emit_line = |line: &mut Vec<u8>| {
+            let message = String::from_utf8_lossy(line).trim().to_string();
+            if !message.is_empty() {
+                let _ = sink.app.emit(
+                    "git-progress",
+                    GitProgress {
+                        operation: sink.operation.clone(),
+                        message,
+                    },
+                );
+            }
+            line.clear();
+        };
+
+        if let Some(mut pipe) = pipe {
+            while let Ok(n) = pipe.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                all.extend_from_slice(&buf[..n]);
+                for &byte in &buf[..n] {
+                    if byte == b'\r' || byte == b'\n' {
+                        emit_line(&mut line);
+                    } else {
+                        line.push(byte);
+                    }
+                }
+            }
+        }
+        emit_line(&mut line);
+
+        all
+    })
 }
 
-/// Execute a git command with timeout
-fn run_git_command(workspace_path: &str, args: &[&str], timeout_secs: u64) -> Result<String, String> {
-    // Only log important commands (not status checks)
-    let is_status_check = args.contains(&"status") || args.contains(&"rev-parse") || args.contains(&"rev-list");
+/// Execute a git command with timeout, streaming its progress output as `git-progress`
+/// events. Pass `--progress` in `args` so git reports progress even without a TTY.
+fn run_git_command_with_progress(
+    app: &AppHandle,
+    operation: &str,
+    workspace_path: &str,
+    args: &[&str],
+    timeout_secs: u64,
+) -> Result<String, String> {
+    let sink = ProgressSink {
+        app: app.clone(),
+        operation: operation.to_string(),
+    };
+    execute_git(workspace_path, args, timeout_secs, &[0], &[], Some(sink))
+}
+
+/// Execute a git command with timeout, treating any exit code in `ok_codes` as success
+/// (e.g. `diff --no-index` exits 1 when the files differ)
+fn run_git_command_with_codes(
+    workspace_path: &str,
+    args: &[&str],
+    timeout_secs: u64,
+    ok_codes: &[i32],
+) -> Result<String, String> {
+    execute_git(workspace_path, args, timeout_secs, ok_codes, &[], None)
+}
+
+/// Run git with `env` set on top of the inherited environment, for settings that must
+/// win over the user's own (e.g. `GIT_EDITOR`, which beats any `-c core.editor`)
+fn run_git_command_with_env(
+    workspace_path: &str,
+    args: &[&str],
+    timeout_secs: u64,
+    env: &[(&str, &str)],
+) -> Result<String, String> {
+    execute_git(workspace_path, args, timeout_secs, &[0], env, None)
+}
+
+fn execute_git(
+    workspace_path: &str,
+    args: &[&str],
+    timeout_secs: u64,
+    ok_codes: &[i32],
+    env: &[(&str, &str)],
+    progress: Option<ProgressSink>,
+) -> Result<String, String> {
+    // Only log important commands (not status checks or the remote poller's quiet fetches)
+    let is_status_check = args.contains(&"status")
+        || args.contains(&"rev-parse")
+        || args.contains(&"rev-list")
+        || (args.contains(&"fetch") && args.contains(&"--quiet"));
     if !is_status_check {
         eprintln!("[Git Shell] Running: git {}", args.join(" "));
     }
 
     let mut child = Command::new("git")
         .args(args)
+        .envs(env.iter().copied())
         .current_dir(workspace_path)
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .spawn()
         .map_err(|e| format!("Failed to execute git: {}. Make sure Git is installed.", e))?;
 
-    // Wait with timeout
-    let timeout = Duration::from_secs(timeout_secs);
-    match child.wait_timeout(timeout).map_err(|e| format!("Error waiting for git: {}", e))? {
+    let stdout_reader = read_pipe(child.stdout.take());
+    let stderr_reader = match progress {
+        Some(sink) => read_progress_pipe(child.stderr.take(), sink),
+        None => read_pipe(child.stderr.take()),
+    };
+
+    // Wait with timeout (0 waits indefinitely)
+    let status = if timeout_secs == 0 {
+        Some(child.wait().map_err(|e| format!("Error waiting for git: {}", e))?)
+    } else {
+        let timeout = Duration::from_secs(timeout_secs);
+        child.wait_timeout(timeout).map_err(|e| format!("Error waiting for git: {}", e))?
+    };
+    match status {
         Some(status) => {
-            let output = child.wait_with_output()
-                .map_err(|e| format!("Failed to read git output: {}", e))?;
+            let stdout = stdout_reader.join().unwrap_or_default();
+            let stderr = stderr_reader.join().unwrap_or_default();
 
-            if status.success() {
+            let succeeded = status.code().map(|code| ok_codes.contains(&code)).unwrap_or(false);
+            if succeeded {
                 if !is_status_check {
                     eprintln!("[Git Shell] ✓ Command succeeded");
                 }
-                Ok(String::from_utf8_lossy(&output.stdout).to_string())
+                Ok(String::from_utf8_lossy(&stdout).to_string())
             } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
+                let stderr = String::from_utf8_lossy(&stderr);
                 // Don't log "no upstream" errors - they're expected
                 if !stderr.contains("no upstream configured") {
                     eprintln!("[Git Shell] ✗ Command failed: {}", stderr);
@@ -70,15 +570,207 @@ fn run_git_command(workspace_path: &str, args: &[&str], timeout_secs: u64) -> Re
         None => {
             // Timeout - kill the process
             child.kill().ok();
+            child.wait().ok();
             eprintln!("[Git Shell] ✗ Command timed out after {} seconds", timeout_secs);
             Err(format!("Git operation timed out after {} seconds", timeout_secs))
         }
     }
 }
 
+/// Reject an empty argument, or one git would parse as an option (e.g. a "branch" of
+/// `-f` or `--orphan=x`). `what` names the argument in the error.
+fn validate_git_arg(what: &str, value: &str) -> Result<(), String> {
+    if value.trim().is_empty() || value.starts_with('-') {
+        return Err(format!("Invalid {}: {}", what, value));
+    }
+    Ok(())
+}
+
+/// Join a user-supplied relative path onto the workspace, rejecting absolute paths
+/// and `..` components that would escape it
+pub(crate) fn resolve_workspace_path(workspace_path: &str, file_path: &str) -> Result<PathBuf, String> {
+    let mut resolved = PathBuf::from(workspace_path);
+    let mut depth = 0usize;
+
+    for component in Path::new(file_path).components() {
+        match component {
+            Component::Normal(part) => {
+                resolved.push(part);
+                depth += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir if depth > 0 => {
+                resolved.pop();
+                depth -= 1;
+            }
+            _ => return Err(format!("Path escapes the workspace: {}", file_path)),
+        }
+    }
+
+    if depth == 0 {
+        return Err(format!("Not a file inside the workspace: {}", file_path));
+    }
+    Ok(resolved)
+}
+
+/// Parse `git log` output produced with `LOG_ENTRY_FORMAT`
+fn parse_log_entries(output: &str) -> Vec<GitLogEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\x1f').collect();
+            if fields.len() < 5 {
+                return None;
+            }
+            Some(GitLogEntry {
+                hash: fields[0].to_string(),
+                author_name: fields[1].to_string(),
+                author_email: fields[2].to_string(),
+                timestamp: fields[3].parse().unwrap_or(0),
+                subject: fields[4].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Parse `git blame --line-porcelain` output, where every line carries a full header:
+/// `<hash> <orig line> <final line> [<group size>]`, `key value` lines, then the
+/// tab-prefixed content
+fn parse_line_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut current: Option<BlameLine> = None;
+
+    for line in output.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some(mut blame) = current.take() {
+                blame.content = content.to_string();
+                lines.push(blame);
+            }
+        } else if let Some(blame) = current.as_mut() {
+            if let Some(author) = line.strip_prefix("author ") {
+                blame.author = author.to_string();
+            } else if let Some(time) = line.strip_prefix("author-time ") {
+                blame.timestamp = time.parse().unwrap_or(0);
+            }
+        } else {
+            let mut parts = line.split(' ');
+            let commit = parts.next().unwrap_or("");
+            let line_number = parts.nth(1).and_then(|n| n.parse().ok());
+            if let Some(line_number) = line_number {
+                current = Some(BlameLine {
+                    line_number,
+                    content: String::new(),
+                    author: String::new(),
+                    timestamp: 0,
+                    commit: commit.to_string(),
+                });
+            }
+        }
+    }
+
+    lines
+}
+
+/// Paths with unresolved merge conflicts
+fn list_conflicted_files(workspace_path: &str) -> Result<Vec<String>, String> {
+    let output = run_git_command(workspace_path, &["diff", "--name-only", "--diff-filter=U"], 5)?;
+    Ok(output.lines().filter(|line| !line.is_empty()).map(|line| line.to_string()).collect())
+}
+
+/// Tracked files that the current ignore rules would exclude
+fn list_tracked_ignored(workspace_path: &str) -> Result<Vec<String>, String> {
+    let output = run_git_command(workspace_path, &["ls-files", "-z", "--cached", "--ignored", "--exclude-standard"], 10)?;
+    Ok(output.split('\0').filter(|path| !path.is_empty()).map(|path| path.to_string()).collect())
+}
+
+/// An operation that stopped part-way (usually on conflicts) and awaits --continue/--abort
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InProgressOperation {
+    Merge,
+    Rebase,
+    CherryPick,
+}
+
+impl InProgressOperation {
+    /// The git subcommand that owns the operation
+    fn command(self) -> &'static str {
+        match self {
+            InProgressOperation::Merge => "merge",
+            InProgressOperation::Rebase => "rebase",
+            InProgressOperation::CherryPick => "cherry-pick",
+        }
+    }
+}
+
+/// Resolve a path inside the git dir; `--git-path` also handles worktrees, where `.git` is a file
+fn git_dir_path(workspace_path: &str, name: &str) -> Option<PathBuf> {
+    let output = run_git_command(workspace_path, &["rev-parse", "--git-path", name], 5).ok()?;
+    Some(Path::new(workspace_path).join(output.trim()))
+}
+
+fn detect_in_progress(workspace_path: &str) -> Option<InProgressOperation> {
+    let exists = |name: &str| git_dir_path(workspace_path, name).map(|path| path.exists()).unwrap_or(false);
+
+    // A conflicted rebase step can leave MERGE_HEAD-like state behind, so check rebase first
+    if exists("rebase-merge") || exists("rebase-apply") {
+        Some(InProgressOperation::Rebase)
+    } else if exists("MERGE_HEAD") {
+        Some(InProgressOperation::Merge)
+    } else if exists("CHERRY_PICK_HEAD") {
+        Some(InProgressOperation::CherryPick)
+    } else {
+        None
+    }
+}
+
+fn is_repository(workspace_path: &str) -> bool {
+    run_git_command(workspace_path, &["rev-parse", "--git-dir"], 5).is_ok()
+}
+
+/// Fail fast with a recognisable error instead of git's raw "not a git repository" text.
+/// Errors are prefixed with `NOT_A_REPOSITORY:` so the UI can offer to initialize one.
+fn ensure_repository(workspace_path: &str) -> Result<(), String> {
+    if is_repository(workspace_path) {
+        Ok(())
+    } else {
+        Err(format!("NOT_A_REPOSITORY: {} is not a git repository", workspace_path))
+    }
+}
+
+/// Whether HEAD points at a commit (false in a freshly initialized repo)
+fn has_commits(workspace_path: &str) -> bool {
+    run_git_command(workspace_path, &["rev-parse", "--verify", "--quiet", "HEAD"], 5).is_ok()
+}
+
+/// Commits ahead of / behind the upstream branch, (0, 0) when no upstream is set
+fn get_ahead_behind(workspace_path: &str) -> (usize, usize) {
+    match run_git_command(workspace_path, &["rev-list", "--left-right", "--count", "HEAD...@{u}"], 5) {
+        Ok(output) => {
+            let parts: Vec<&str> = output.trim().split_whitespace().collect();
+            if parts.len() >= 2 {
+                let ahead = parts[0].parse().unwrap_or(0);
+                let behind = parts[1].parse().unwrap_or(0);
+                (ahead, behind)
+            } else {
+                (0, 0)
+            }
+        }
+        Err(_) => (0, 0), // No upstream branch set
+    }
+}
+
 #[tauri::command]
-pub async fn git_push(workspace_path: String, remote_name: Option<String>) -> Result<(), String> {
+pub async fn git_push(
+    app: AppHandle,
+    workspace_path: String,
+    remote_name: Option<String>,
+    timeout_secs: Option<u64>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
     let remote = remote_name.unwrap_or_else(|| "origin".to_string());
+    let timeout = timeout_secs.unwrap_or(NETWORK_TIMEOUT_SECS);
 
     // Get current branch
     let branch = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
@@ -87,47 +779,155 @@ pub async fn git_push(workspace_path: String, remote_name: Option<String>) -> Re
     // Check if upstream is set
     let has_upstream = run_git_command(&workspace_path, &["rev-parse", "--abbrev-ref", &format!("{}@{{u}}", branch)], 5).is_ok();
 
+    let mut args = vec!["push", "--progress"];
     // Push with -u flag if no upstream, otherwise just push
-    if has_upstream {
-        run_git_command(&workspace_path, &["push", &remote, branch], 30)?;
-    } else {
+    if !has_upstream {
         eprintln!("[Git Shell] No upstream set, using -u flag to set tracking");
-        run_git_command(&workspace_path, &["push", "-u", &remote, branch], 30)?;
+        args.push("-u");
+    }
+    // Only overwrite the remote branch if it's still where we last saw it
+    if force.unwrap_or(false) {
+        args.push("--force-with-lease");
     }
+    args.extend([remote.as_str(), branch]);
 
+    run_git_command_with_progress(&app, "push", &workspace_path, &args, timeout)?;
     Ok(())
 }
 
 #[tauri::command]
-pub async fn git_pull(workspace_path: String, remote_name: Option<String>) -> Result<(), String> {
+pub async fn git_pull(
+    app: AppHandle,
+    workspace_path: String,
+    remote_name: Option<String>,
+    timeout_secs: Option<u64>,
+    rebase: Option<bool>,
+) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
     let remote = remote_name.unwrap_or_else(|| "origin".to_string());
+    let timeout = timeout_secs.unwrap_or(NETWORK_TIMEOUT_SECS);
 
     // Get current branch
     let branch = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
     let branch = branch.trim();
 
     // Pull with branch name
-    run_git_command(&workspace_path, &["pull", &remote, branch], 30)?;
-    Ok(())
+    pull_from_remote(&app, &workspace_path, &remote, branch, rebase.unwrap_or(false), timeout)
+}
+
+/// Pull `branch` from `remote`, merging by default or rebasing local commits on top.
+/// A rebase that stops on conflicts fails with a `REBASE_CONFLICT:` prefixed error
+/// listing the conflicted files followed by git's own output.
+fn pull_from_remote(
+    app: &AppHandle,
+    workspace_path: &str,
+    remote: &str,
+    branch: &str,
+    rebase: bool,
+    timeout_secs: u64,
+) -> Result<(), String> {
+    let mut args = vec!["pull", "--progress"];
+    if rebase {
+        args.push("--rebase");
+    }
+    args.extend([remote, branch]);
+
+    match run_git_command_with_progress(app, "pull", workspace_path, &args, timeout_secs) {
+        Ok(_) => Ok(()),
+        Err(e) if rebase => {
+            let conflicted = list_conflicted_files(workspace_path).unwrap_or_default();
+            if conflicted.is_empty() {
+                Err(e)
+            } else {
+                Err(format!("REBASE_CONFLICT: {}\n{}", conflicted.join(", "), e.trim()))
+            }
+        }
+        Err(e) => Err(e),
+    }
 }
 
 #[tauri::command]
-pub async fn git_commit(workspace_path: String, options: GitCommitOptions) -> Result<String, String> {
+pub async fn git_commit(
+    workspace_path: String,
+    options: GitCommitOptions,
+    stage_all: Option<bool>,
+) -> Result<GitCommitResult, String> {
+    ensure_repository(&workspace_path)?;
+
+    let warning = validate_commit_message(&options.message, options.allow_empty_message)?;
+
     // Configure user if needed
     run_git_command(&workspace_path, &["config", "user.name", &options.author_name], 5)?;
     run_git_command(&workspace_path, &["config", "user.email", &options.author_email], 5)?;
 
-    // Stage all changes
-    run_git_command(&workspace_path, &["add", "-A"], 10)?;
+    // Stage all changes, unless the caller curated the index itself
+    if stage_all.unwrap_or(true) {
+        run_git_command(&workspace_path, &["add", "-A"], 10)?;
+    }
+
+    let hash = run_commit(&workspace_path, &options, &[])?;
+    Ok(GitCommitResult { hash, warning })
+}
+
+/// Commit the index (or, when `paths` is non-empty, only those paths) and return the
+/// new HEAD hash
+fn run_commit(workspace_path: &str, options: &GitCommitOptions, paths: &[String]) -> Result<String, String> {
+    if options.amend && !has_commits(workspace_path) {
+        return Err("Cannot amend: the repository has no commits yet".to_string());
+    }
 
     // Commit
-    run_git_command(&workspace_path, &["commit", "-m", &options.message], 10)?;
+    let mut args = vec!["commit"];
+    if options.amend {
+        args.push("--amend");
+    }
+    if options.message.trim().is_empty() {
+        args.extend(["--allow-empty-message", "-m", ""]);
+    } else {
+        args.extend(["-m", options.message.as_str()]);
+    }
+    if !paths.is_empty() {
+        args.push("--");
+        args.extend(paths.iter().map(|path| path.as_str()));
+    }
+    run_git_command(workspace_path, &args, 10)?;
 
     // Get commit hash
-    let hash = run_git_command(&workspace_path, &["rev-parse", "HEAD"], 5)?;
+    let hash = run_git_command(workspace_path, &["rev-parse", "HEAD"], 5)?;
     Ok(hash.trim().to_string())
 }
 
+/// Stage and commit exactly `files`, leaving anything else that's staged out of the commit
+#[tauri::command]
+pub async fn git_commit_files(
+    workspace_path: String,
+    files: Vec<String>,
+    options: GitCommitOptions,
+) -> Result<GitCommitResult, String> {
+    ensure_repository(&workspace_path)?;
+
+    if files.is_empty() {
+        return Err("No files to commit".to_string());
+    }
+    for file in &files {
+        resolve_workspace_path(&workspace_path, file)?;
+    }
+    let warning = validate_commit_message(&options.message, options.allow_empty_message)?;
+
+    // Configure user if needed
+    run_git_command(&workspace_path, &["config", "user.name", &options.author_name], 5)?;
+    run_git_command(&workspace_path, &["config", "user.email", &options.author_email], 5)?;
+
+    // Stage only the chosen paths (this also records deletions)
+    let mut add_args = vec!["add", "--"];
+    add_args.extend(files.iter().map(|file| file.as_str()));
+    run_git_command(&workspace_path, &add_args, 10)?;
+
+    let hash = run_commit(&workspace_path, &options, &files)?;
+    Ok(GitCommitResult { hash, warning })
+}
+
 #[tauri::command]
 pub async fn git_initialize(workspace_path: String, config: GitConfig) -> Result<(), String> {
     eprintln!("[Git Init] Initializing repository at: {}", workspace_path);
@@ -190,93 +990,115 @@ pub async fn git_initialize(workspace_path: String, config: GitConfig) -> Result
 
 #[tauri::command]
 pub async fn git_get_status(workspace_path: String) -> Result<GitStatus, String> {
+    ensure_repository(&workspace_path)?;
+
     // Get current branch
     let branch_output = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
-    let branch = branch_output.trim().to_string();
+    let mut branch = branch_output.trim().to_string();
 
-    // Get status in porcelain format
-    let status_output = run_git_command(&workspace_path, &["status", "--porcelain"], 5)?;
+    // An empty branch name means HEAD is detached at a specific commit
+    let is_detached = branch.is_empty();
+    if is_detached {
+        let short_hash = run_git_command(&workspace_path, &["rev-parse", "--short", "HEAD"], 5)?;
+        branch = format!("(detached: {})", short_hash.trim());
+    }
 
-    let mut modified_count = 0;
-    let mut staged_count = 0;
-    let mut modified_files = Vec::new();
+    // Get status in porcelain format; -z keeps paths with spaces or non-ASCII
+    // characters unquoted and unescaped
+    let status_output = run_git_command(&workspace_path, &["status", "--porcelain", "-z"], 5)?;
+    let summary = summarize_porcelain(&status_output);
 
-    for line in status_output.lines() {
-        if line.is_empty() {
-            continue;
-        }
+    // Get ahead/behind counts (a detached HEAD has no upstream)
+    let (ahead, behind) = if is_detached {
+        (0, 0)
+    } else {
+        get_ahead_behind(&workspace_path)
+    };
 
-        // Porcelain format: XY filename
-        // X = staged status, Y = unstaged status
-        let chars: Vec<char> = line.chars().collect();
-        if chars.len() < 3 {
-            continue;
-        }
-
-        let staged_char = chars[0];
-        let unstaged_char = chars[1];
-        let filename = line[3..].to_string();
-
-        modified_files.push(filename);
-
-        // Check if staged
-        if staged_char != ' ' && staged_char != '?' {
-            staged_count += 1;
-        }
-
-        // Check if modified (unstaged)
-        if unstaged_char != ' ' {
-            modified_count += 1;
-        }
-    }
-
-    // Get ahead/behind counts
-    let (ahead, behind) = match run_git_command(&workspace_path, &["rev-list", "--left-right", "--count", "HEAD...@{u}"], 5) {
-        Ok(output) => {
-            let parts: Vec<&str> = output.trim().split_whitespace().collect();
-            if parts.len() >= 2 {
-                let ahead = parts[0].parse().unwrap_or(0);
-                let behind = parts[1].parse().unwrap_or(0);
-                (ahead, behind)
-            } else {
-                (0, 0)
+    // Latest commit (fails on an empty repository)
+    let last_commit = run_git_command(&workspace_path, &["log", "-1", "--pretty=format:%H%x1f%s%x1f%at"], 5)
+        .ok()
+        .and_then(|output| {
+            let fields: Vec<&str> = output.trim_end().split('\x1f').collect();
+            if fields.len() < 3 {
+                return None;
             }
-        }
-        Err(_) => (0, 0), // No upstream branch set
-    };
+            Some(LastCommit {
+                hash: fields[0].to_string(),
+                subject: fields[1].to_string(),
+                timestamp: fields[2].parse().unwrap_or(0),
+            })
+        });
 
     Ok(GitStatus {
         branch,
-        modified_count,
-        staged_count,
+        is_detached,
         ahead,
         behind,
-        is_clean: modified_count == 0 && staged_count == 0,
-        modified_files,
+        is_clean: summary.modified_count == 0 && summary.staged_count == 0,
+        modified_count: summary.modified_count,
+        staged_count: summary.staged_count,
+        modified_files: summary.modified_files,
+        renamed_files: summary.renamed_files,
+        last_commit,
+        has_conflicts: summary.has_conflicts,
     })
 }
 
 #[tauri::command]
-pub async fn git_sync(workspace_path: String, remote_name: Option<String>) -> Result<(), String> {
+pub async fn git_sync(
+    app: AppHandle,
+    workspace_path: String,
+    remote_name: Option<String>,
+    timeout_secs: Option<u64>,
+    rebase: Option<bool>,
+) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
     let remote = remote_name.unwrap_or_else(|| "origin".to_string());
+    let timeout = timeout_secs.unwrap_or(NETWORK_TIMEOUT_SECS);
 
     // Get current branch
     let branch = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
     let branch = branch.trim();
 
     // Pull first
-    run_git_command(&workspace_path, &["pull", &remote, branch], 30)?;
+    pull_from_remote(&app, &workspace_path, &remote, branch, rebase.unwrap_or(false), timeout)?;
     // Then push
-    run_git_command(&workspace_path, &["push", &remote, branch], 30)?;
+    run_git_command_with_progress(&app, "push", &workspace_path, &["push", "--progress", &remote, branch], timeout)?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn git_is_repository(workspace_path: String) -> Result<bool, String> {
-    match run_git_command(&workspace_path, &["rev-parse", "--git-dir"], 5) {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
-    }
+    Ok(is_repository(&workspace_path))
+}
+
+/// Installed git version (e.g. "2.44.0"), or `None` when git isn't on PATH
+#[tauri::command]
+pub async fn git_version() -> Result<Option<String>, String> {
+    let output = match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Ok(None),
+    };
+
+    // "git version 2.44.0" (Apple Git adds a suffix like " (Apple Git-143)")
+    let text = String::from_utf8_lossy(&output.stdout);
+    Ok(text
+        .trim()
+        .strip_prefix("git version ")
+        .map(|version| version.split_whitespace().next().unwrap_or(version).to_string()))
+}
+
+/// Everything the sync UI needs to know about the local git setup, in one call
+#[tauri::command]
+pub async fn git_check_environment() -> Result<GitEnvironment, String> {
+    let git_version = git_version().await?;
+    let lfs_available = git_version.is_some() && git_lfs_available().await?;
+    Ok(GitEnvironment {
+        git_version,
+        lfs_available,
+    })
 }
 
 #[tauri::command]
@@ -292,3 +1114,916 @@ pub async fn git_lfs_available() -> Result<bool, String> {
         Err(_) => Ok(false),
     }
 }
+
+#[tauri::command]
+pub async fn generate_note_changelog(workspace_path: String, file_rel: String) -> Result<String, String> {
+    // One record per commit: \x1e starts a record, \x1f separates header fields,
+    // followed by numstat lines ("added<TAB>deleted<TAB>path")
+    let output = run_git_command(
+        &workspace_path,
+        &[
+            "log",
+            "--follow",
+            "--numstat",
+            "--date=short",
+            "--pretty=format:%x1e%H%x1f%an%x1f%ad%x1f%s",
+            "--",
+            &file_rel,
+        ],
+        15,
+    )?;
+
+    let mut changelog = format!("# Changelog: {}\n\n", file_rel);
+    let mut entry_count = 0;
+
+    for record in output.split('\x1e') {
+        let mut lines = record.lines();
+        let header = match lines.next() {
+            Some(header) if !header.trim().is_empty() => header,
+            _ => continue,
+        };
+
+        let fields: Vec<&str> = header.split('\x1f').collect();
+        if fields.len() < 4 {
+            continue;
+        }
+        let (hash, author, date, subject) = (fields[0], fields[1], fields[2], fields[3]);
+
+        // Sum numstat lines (binary files report "-" and are counted as 0)
+        let (mut added, mut deleted) = (0usize, 0usize);
+        for line in lines {
+            let parts: Vec<&str> = line.split('\t').collect();
+            if parts.len() >= 3 {
+                added += parts[0].parse::<usize>().unwrap_or(0);
+                deleted += parts[1].parse::<usize>().unwrap_or(0);
+            }
+        }
+
+        let short_hash = &hash[..hash.len().min(7)];
+        changelog.push_str(&format!(
+            "- **{}** · {} · {} (`{}`, +{} / -{})\n",
+            date, author, subject, short_hash, added, deleted
+        ));
+        entry_count += 1;
+    }
+
+    if entry_count == 0 {
+        changelog.push_str("_No committed history for this note yet._\n");
+    }
+
+    Ok(changelog)
+}
+
+#[tauri::command]
+pub async fn git_worktree_add(
+    workspace_path: String,
+    path: String,
+    branch: Option<String>,
+) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    validate_git_arg("worktree path", &path)?;
+    if let Some(branch) = &branch {
+        validate_git_arg("branch name", branch)?;
+    }
+
+    // Without a branch the worktree is created detached at HEAD, for throwaway experiments
+    match branch {
+        Some(branch) => run_git_command(&workspace_path, &["worktree", "add", &path, &branch], 30)?,
+        None => run_git_command(&workspace_path, &["worktree", "add", "--detach", &path], 30)?,
+    };
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_worktree_list(workspace_path: String) -> Result<Vec<WorktreeInfo>, String> {
+    let output = run_git_command(&workspace_path, &["worktree", "list", "--porcelain"], 5)?;
+
+    // Porcelain output: blank-line separated blocks of "worktree <path>", "HEAD <sha>",
+    // then "branch refs/heads/<name>" or "detached"
+    let mut worktrees = Vec::new();
+    for block in output.split("\n\n") {
+        let mut info = WorktreeInfo {
+            path: String::new(),
+            head: String::new(),
+            branch: None,
+            is_detached: false,
+        };
+
+        for line in block.lines() {
+            if let Some(path) = line.strip_prefix("worktree ") {
+                info.path = path.to_string();
+            } else if let Some(head) = line.strip_prefix("HEAD ") {
+                info.head = head.to_string();
+            } else if let Some(branch) = line.strip_prefix("branch ") {
+                info.branch = Some(branch.trim_start_matches("refs/heads/").to_string());
+            } else if line == "detached" {
+                info.is_detached = true;
+            }
+        }
+
+        if !info.path.is_empty() {
+            worktrees.push(info);
+        }
+    }
+
+    Ok(worktrees)
+}
+
+#[tauri::command]
+pub async fn git_worktree_remove(workspace_path: String, path: String, force: bool) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    if force {
+        run_git_command(&workspace_path, &["worktree", "remove", "--force", &path], 10)?;
+    } else {
+        run_git_command(&workspace_path, &["worktree", "remove", &path], 10)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn start_remote_poll(
+    app: AppHandle,
+    remote_poll: tauri::State<'_, RemotePollManager>,
+    workspace_path: String,
+    interval_secs: u64,
+) -> Result<(), String> {
+    remote_poll.start(app, workspace_path, interval_secs)
+}
+
+#[tauri::command]
+pub async fn stop_remote_poll(
+    remote_poll: tauri::State<'_, RemotePollManager>,
+    workspace_path: String,
+) -> Result<(), String> {
+    remote_poll.stop(&workspace_path)
+}
+
+#[tauri::command]
+pub async fn git_squash_last(
+    workspace_path: String,
+    count: usize,
+    message: String,
+    force: bool,
+) -> Result<GitCommitResult, String> {
+    ensure_repository(&workspace_path)?;
+
+    if count < 2 {
+        return Err("Need at least 2 commits to squash".to_string());
+    }
+    let warning = validate_commit_message(&message, false)?;
+
+    // HEAD~count must exist, i.e. there must be more than `count` commits
+    let total: usize = run_git_command(&workspace_path, &["rev-list", "--count", "HEAD"], 5)?
+        .trim()
+        .parse()
+        .unwrap_or(0);
+    if count >= total {
+        return Err(format!("Cannot squash {} commits: branch only has {}", count, total));
+    }
+
+    // Refuse to rewrite commits that already exist on the upstream unless forced
+    if !force {
+        if let Ok(output) = run_git_command(&workspace_path, &["rev-list", "--count", "@{u}..HEAD"], 5) {
+            let unpushed: usize = output.trim().parse().unwrap_or(0);
+            if count > unpushed {
+                return Err(format!(
+                    "Only {} of the last {} commits are unpushed; squashing would rewrite pushed history. Pass force to proceed.",
+                    unpushed, count
+                ));
+            }
+        }
+    }
+
+    let target = format!("HEAD~{}", count);
+    run_git_command(&workspace_path, &["reset", "--soft", &target], 10)?;
+    run_git_command(&workspace_path, &["commit", "-m", &message], 10)?;
+
+    let hash = run_git_command(&workspace_path, &["rev-parse", "HEAD"], 5)?;
+    Ok(GitCommitResult {
+        hash: hash.trim().to_string(),
+        warning,
+    })
+}
+
+#[tauri::command]
+pub async fn git_checkout_file_from(
+    workspace_path: String,
+    branch: String,
+    file_rel: String,
+) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    // Make sure the file exists on the source branch for a clear error message
+    let object = format!("{}:{}", branch, file_rel);
+    if run_git_command(&workspace_path, &["cat-file", "-e", &object], 5).is_err() {
+        return Err(format!("'{}' does not exist on branch '{}'", file_rel, branch));
+    }
+
+    run_git_command(&workspace_path, &["checkout", &branch, "--", &file_rel], 10)?;
+    run_git_command(&workspace_path, &["add", "--", &file_rel], 5)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_log(workspace_path: String, limit: Option<usize>) -> Result<Vec<GitLogEntry>, String> {
+    if !has_commits(&workspace_path) {
+        return Ok(Vec::new());
+    }
+
+    let limit = limit.unwrap_or(50).to_string();
+    let output = run_git_command(&workspace_path, &["log", LOG_ENTRY_FORMAT, "-n", &limit], 10)?;
+    Ok(parse_log_entries(&output))
+}
+
+#[tauri::command]
+pub async fn git_diff(workspace_path: String, file_path: String, staged: bool) -> Result<String, String> {
+    if staged {
+        return run_git_command(&workspace_path, &["diff", "--cached", "--", &file_path], 10);
+    }
+
+    let is_tracked = run_git_command(&workspace_path, &["ls-files", "--error-unmatch", "--", &file_path], 5).is_ok();
+    if is_tracked {
+        run_git_command(&workspace_path, &["diff", "--", &file_path], 10)
+    } else {
+        // Show an untracked file as entirely added; exit code 1 just means "differs"
+        run_git_command_with_codes(&workspace_path, &["diff", "--no-index", "--", "/dev/null", &file_path], 10, &[0, 1])
+    }
+}
+
+#[tauri::command]
+pub async fn git_branch_list(workspace_path: String) -> Result<Vec<BranchInfo>, String> {
+    // Full refnames tell local and remote-tracking branches apart unambiguously;
+    // %1f is for-each-ref's hex escape for the \x1f separator
+    let output = run_git_command(
+        &workspace_path,
+        &["branch", "-a", "--format=%(refname)%1f%(HEAD)"],
+        5,
+    )?;
+
+    let mut local = Vec::new();
+    let mut remote = Vec::new();
+
+    for line in output.lines() {
+        let mut fields = line.split('\x1f');
+        let refname = fields.next().unwrap_or("").trim();
+        let is_current = fields.next().map(|head| head.trim() == "*").unwrap_or(false);
+
+        if let Some(name) = refname.strip_prefix("refs/heads/") {
+            local.push(BranchInfo {
+                name: name.to_string(),
+                is_current,
+                is_remote: false,
+            });
+        } else if let Some(name) = refname.strip_prefix("refs/remotes/") {
+            // Skip symbolic refs like origin/HEAD
+            if name.ends_with("/HEAD") {
+                continue;
+            }
+            remote.push(BranchInfo {
+                name: name.to_string(),
+                is_current: false,
+                is_remote: true,
+            });
+        }
+    }
+
+    // Drop remote-tracking branches that just mirror a local branch of the same name
+    remote.retain(|branch| {
+        let short = branch.name.split_once('/').map(|(_, rest)| rest).unwrap_or(branch.name.as_str());
+        !local.iter().any(|l| l.name == short)
+    });
+
+    local.extend(remote);
+    Ok(local)
+}
+
+#[tauri::command]
+pub async fn git_checkout(workspace_path: String, branch: String, create: bool) -> Result<GitStatus, String> {
+    ensure_repository(&workspace_path)?;
+
+    validate_git_arg("branch name", &branch)?;
+
+    // Git's own error (e.g. "local changes would be overwritten") is passed through as-is
+    if create {
+        run_git_command(&workspace_path, &["checkout", "-b", &branch], 10)?;
+    } else {
+        run_git_command(&workspace_path, &["checkout", &branch], 10)?;
+    }
+
+    git_get_status(workspace_path).await
+}
+
+#[tauri::command]
+pub async fn git_stash(workspace_path: String, message: Option<String>) -> Result<String, String> {
+    ensure_repository(&workspace_path)?;
+
+    match message.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        Some(message) => run_git_command(&workspace_path, &["stash", "push", "-m", message], 10),
+        None => run_git_command(&workspace_path, &["stash", "push"], 10),
+    }
+}
+
+#[tauri::command]
+pub async fn git_stash_pop(workspace_path: String) -> Result<String, String> {
+    ensure_repository(&workspace_path)?;
+
+    run_git_command(&workspace_path, &["stash", "pop"], 10)
+}
+
+#[tauri::command]
+pub async fn git_stash_list(workspace_path: String) -> Result<Vec<String>, String> {
+    let output = run_git_command(&workspace_path, &["stash", "list"], 5)?;
+    Ok(output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn git_stage_file(workspace_path: String, file_path: String) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    run_git_command(&workspace_path, &["add", "--", &file_path], 5)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_unstage_file(workspace_path: String, file_path: String) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    if has_commits(&workspace_path) {
+        run_git_command(&workspace_path, &["reset", "-q", "HEAD", "--", &file_path], 5)?;
+    } else {
+        // No HEAD to reset to yet; just drop the file from the index
+        run_git_command(&workspace_path, &["rm", "--cached", "-q", "--", &file_path], 5)?;
+    }
+    Ok(())
+}
+
+/// How `git_discard_file` gets a path back to its last committed state
+#[derive(Debug, PartialEq)]
+enum DiscardAction {
+    /// Untracked: there is no committed version, so discarding means deleting
+    Delete,
+    /// Added in the index only (new file, or the destination of a staged rename/copy):
+    /// drop it from the index, then delete it
+    UnstageAndDelete,
+    /// Tracked: restore both the index and the working tree from HEAD, so staged edits go too
+    RestoreFromHead,
+}
+
+fn discard_action(entry: &PorcelainEntry) -> DiscardAction {
+    match entry.index {
+        '?' => DiscardAction::Delete,
+        'A' | 'R' | 'C' => DiscardAction::UnstageAndDelete,
+        _ => DiscardAction::RestoreFromHead,
+    }
+}
+
+#[tauri::command]
+pub async fn git_discard_file(workspace_path: String, file_path: String) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    let full_path = resolve_workspace_path(&workspace_path, &file_path)?;
+
+    let status = run_git_command(&workspace_path, &["status", "--porcelain", "-z", "--", &file_path], 5)?;
+    let entry = match parse_porcelain_z(&status).into_iter().next() {
+        Some(entry) => entry,
+        None => return Ok(()), // Already matches HEAD
+    };
+
+    match discard_action(&entry) {
+        DiscardAction::Delete => {
+            std::fs::remove_file(&full_path).map_err(|e| format!("Failed to delete {}: {}", file_path, e))?;
+        }
+        DiscardAction::UnstageAndDelete => {
+            run_git_command(&workspace_path, &["rm", "--cached", "--force", "--quiet", "--", &file_path], 10)?;
+            if full_path.exists() {
+                std::fs::remove_file(&full_path).map_err(|e| format!("Failed to delete {}: {}", file_path, e))?;
+            }
+        }
+        DiscardAction::RestoreFromHead => {
+            run_git_command(&workspace_path, &["checkout", "HEAD", "--", &file_path], 10)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_fetch(workspace_path: String, remote_name: Option<String>) -> Result<RemoteState, String> {
+    ensure_repository(&workspace_path)?;
+
+    let remote = remote_name.unwrap_or_else(|| "origin".to_string());
+
+    // Update remote-tracking refs only; the working tree is untouched
+    run_git_command(&workspace_path, &["fetch", &remote], 30)?;
+
+    let (ahead, behind) = get_ahead_behind(&workspace_path);
+    Ok(RemoteState {
+        workspace_path,
+        ahead,
+        behind,
+    })
+}
+
+#[tauri::command]
+pub async fn git_remote_list(workspace_path: String) -> Result<Vec<RemoteInfo>, String> {
+    let output = run_git_command(&workspace_path, &["remote", "-v"], 5)?;
+
+    // Each remote is listed twice, e.g. "origin\thttps://host/repo.git (fetch)"
+    let mut remotes: Vec<RemoteInfo> = Vec::new();
+    for line in output.lines() {
+        let (name, rest) = match line.split_once('\t') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        let (url, kind) = match rest.rsplit_once(' ') {
+            Some(parts) => parts,
+            None => continue,
+        };
+
+        let index = match remotes.iter().position(|remote| remote.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(RemoteInfo {
+                    name: name.to_string(),
+                    fetch_url: None,
+                    push_url: None,
+                });
+                remotes.len() - 1
+            }
+        };
+
+        match kind {
+            "(fetch)" => remotes[index].fetch_url = Some(url.to_string()),
+            "(push)" => remotes[index].push_url = Some(url.to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(remotes)
+}
+
+#[tauri::command]
+pub async fn git_config_get(workspace_path: String, key: String) -> Result<Option<String>, String> {
+    validate_git_arg("config key", &key)?;
+
+    // Exit code 1 means the key is not set; a set-but-empty value still prints a newline
+    let output = run_git_command_with_codes(&workspace_path, &["config", "--get", &key], 5, &[0, 1])?;
+    if output.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(output.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+#[tauri::command]
+pub async fn git_config_set(workspace_path: String, key: String, value: String) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    validate_git_arg("config key", &key)?;
+
+    run_git_command(&workspace_path, &["config", &key, &value], 5)?;
+    Ok(())
+}
+
+/// Empty a directory while keeping the directory itself
+fn remove_dir_contents(dir: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && !path.is_symlink() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_clone(
+    app: AppHandle,
+    remote_url: String,
+    target_path: String,
+    branch: Option<String>,
+) -> Result<(), String> {
+    let target = Path::new(&target_path);
+    let existed = target.exists();
+    if existed {
+        let is_empty = std::fs::read_dir(target)
+            .map_err(|e| format!("Failed to read target directory: {}", e))?
+            .next()
+            .is_none();
+        if !is_empty {
+            return Err(format!("Target directory is not empty: {}", target_path));
+        }
+    } else {
+        std::fs::create_dir_all(target).map_err(|e| format!("Failed to create target directory: {}", e))?;
+    }
+
+    eprintln!("[Git Clone] Cloning {} into {}", remote_url, target_path);
+
+    // Clone into the (empty) target itself so the command can run from inside it
+    let mut args = vec!["clone", "--progress"];
+    if let Some(branch) = branch.as_deref() {
+        args.extend(["-b", branch]);
+    }
+    args.extend(["--", remote_url.as_str(), "."]);
+
+    if let Err(e) = run_git_command_with_progress(&app, "clone", &target_path, &args, CLONE_TIMEOUT_SECS) {
+        // Leave nothing half-cloned behind: a stray .git would break the next attempt
+        let cleanup = if existed {
+            remove_dir_contents(target)
+        } else {
+            std::fs::remove_dir_all(target)
+        };
+        if let Err(cleanup_error) = cleanup {
+            eprintln!("[Git Clone] ⚠ Warning: Could not clean up {}: {}", target_path, cleanup_error);
+        }
+        return Err(e);
+    }
+
+    if git_lfs_available().await.unwrap_or(false) {
+        match run_git_command_with_progress(&app, "clone", &target_path, &["lfs", "pull"], CLONE_TIMEOUT_SECS) {
+            Ok(_) => eprintln!("[Git Clone] ✓ Git LFS objects pulled"),
+            Err(e) => eprintln!("[Git Clone] ⚠ Warning: Could not pull LFS objects: {}", e),
+        }
+    }
+
+    eprintln!("[Git Clone] ✓ Repository cloned successfully");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_conflicts(workspace_path: String) -> Result<Vec<String>, String> {
+    list_conflicted_files(&workspace_path)
+}
+
+#[tauri::command]
+pub async fn git_reset(
+    workspace_path: String,
+    target: String,
+    mode: ResetMode,
+    confirm_hard: Option<bool>,
+) -> Result<GitStatus, String> {
+    ensure_repository(&workspace_path)?;
+
+    validate_git_arg("reset target", &target)?;
+
+    // A hard reset throws away uncommitted work, so the caller must opt in explicitly
+    if mode == ResetMode::Hard && !confirm_hard.unwrap_or(false) {
+        return Err("HARD_RESET_UNCONFIRMED: A hard reset discards uncommitted changes and must be confirmed".to_string());
+    }
+
+    let flag = match mode {
+        ResetMode::Soft => "--soft",
+        ResetMode::Mixed => "--mixed",
+        ResetMode::Hard => "--hard",
+    };
+    run_git_command(&workspace_path, &["reset", flag, &target], 10)?;
+
+    git_get_status(workspace_path).await
+}
+
+#[tauri::command]
+pub async fn git_show(workspace_path: String, hash: String) -> Result<CommitDetail, String> {
+    // Only accept an abbreviated or full hash, so the argument can't be read as an option or revision expression
+    let is_hash = (4..=40).contains(&hash.len()) && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'));
+    if !is_hash {
+        return Err(format!("Invalid commit hash: {}", hash));
+    }
+
+    // %x1e ends the metadata, since the body may span several lines
+    let output = run_git_command(
+        &workspace_path,
+        &["show", "--pretty=format:%H%x1f%an%x1f%at%x1f%s%x1f%b%x1e", &hash],
+        10,
+    )?;
+
+    let (metadata, diff) = output.split_once('\x1e').unwrap_or((output.as_str(), ""));
+    let fields: Vec<&str> = metadata.splitn(5, '\x1f').collect();
+    if fields.len() < 5 {
+        return Err(format!("Unexpected git show output for {}", hash));
+    }
+
+    Ok(CommitDetail {
+        hash: fields[0].to_string(),
+        author_name: fields[1].to_string(),
+        timestamp: fields[2].parse().unwrap_or(0),
+        subject: fields[3].to_string(),
+        body: fields[4].trim_end().to_string(),
+        diff: diff.trim_start_matches('\n').to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn git_blame(workspace_path: String, file_path: String) -> Result<Vec<BlameLine>, String> {
+    let full_path = resolve_workspace_path(&workspace_path, &file_path)?;
+    let size = std::fs::metadata(&full_path)
+        .map_err(|e| format!("Failed to read {}: {}", file_path, e))?
+        .len();
+    if size > BLAME_MAX_FILE_BYTES {
+        return Err(format!(
+            "File is too large to blame ({} bytes, limit {} bytes)",
+            size, BLAME_MAX_FILE_BYTES
+        ));
+    }
+
+    let output = run_git_command(&workspace_path, &["blame", "--line-porcelain", "--", &file_path], 15)?;
+    Ok(parse_line_porcelain(&output))
+}
+
+#[tauri::command]
+pub async fn git_delete_branch(workspace_path: String, branch: String, force: bool) -> Result<(), String> {
+    ensure_repository(&workspace_path)?;
+
+    validate_git_arg("branch name", &branch)?;
+
+    let current = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
+    if current.trim() == branch {
+        return Err(format!("Cannot delete the currently checked-out branch: {}", branch));
+    }
+
+    // Without force, git refuses unmerged branches; its message is passed through as-is
+    let flag = if force { "-D" } else { "-d" };
+    run_git_command(&workspace_path, &["branch", flag, &branch], 5)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn git_lfs_migrate(app: AppHandle, workspace_path: String) -> Result<LfsMigrateResult, String> {
+    ensure_repository(&workspace_path)?;
+
+    if !git_lfs_available().await.unwrap_or(false) {
+        return Err("Git LFS is not installed. Install it with: brew install git-lfs".to_string());
+    }
+
+    eprintln!("[Git Shell] Migrating workspace/.images/** into Git LFS across all refs");
+    // Progress goes to stderr, the final summary to stdout
+    let summary = run_git_command_with_progress(
+        &app,
+        "lfs-migrate",
+        &workspace_path,
+        &["lfs", "migrate", "import", "--include=workspace/.images/**", "--everything"],
+        LFS_MIGRATE_TIMEOUT_SECS,
+    )?;
+
+    Ok(LfsMigrateResult {
+        summary: summary.trim().to_string(),
+        warning: "History has been rewritten. Every branch must be force-pushed, and other clones need to re-clone or reset to the new history.".to_string(),
+    })
+}
+
+#[tauri::command]
+pub async fn git_gitignore_add(workspace_path: String, patterns: Vec<String>) -> Result<String, String> {
+    ensure_repository(&workspace_path)?;
+
+    let gitignore_path = Path::new(&workspace_path).join(".gitignore");
+    let existing = match std::fs::read_to_string(&gitignore_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read .gitignore: {}", e)),
+    };
+
+    let mut lines: Vec<String> = existing.lines().map(|line| line.to_string()).collect();
+    let mut added = false;
+    for pattern in &patterns {
+        let pattern = pattern.trim();
+        if pattern.is_empty() || lines.iter().any(|line| line.trim() == pattern) {
+            continue;
+        }
+        lines.push(pattern.to_string());
+        added = true;
+    }
+
+    if !added {
+        return Ok(existing);
+    }
+
+    // Only untrack files the new patterns match, not ones that were already tracked-but-ignored
+    let previously_ignored = list_tracked_ignored(&workspace_path)?;
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    crate::notes::write_atomic(&gitignore_path, content.as_bytes())
+        .map_err(|e| format!("Failed to write .gitignore: {}", e))?;
+
+    let newly_ignored: Vec<String> = list_tracked_ignored(&workspace_path)?
+        .into_iter()
+        .filter(|path| !previously_ignored.contains(path))
+        .collect();
+    if !newly_ignored.is_empty() {
+        eprintln!("[Git Shell] Untracking {} newly ignored file(s)", newly_ignored.len());
+        let mut args = vec!["rm", "--cached", "--quiet", "--"];
+        args.extend(newly_ignored.iter().map(|path| path.as_str()));
+        run_git_command(&workspace_path, &args, 10)?;
+    }
+
+    Ok(content)
+}
+
+#[tauri::command]
+pub async fn git_has_changes(workspace_path: String) -> Result<bool, String> {
+    // A single subprocess, for callers that only need "is there anything to commit"
+    let output = run_git_command(&workspace_path, &["status", "--porcelain"], 5)?;
+    Ok(!output.trim().is_empty())
+}
+
+#[tauri::command]
+pub async fn git_current_branch(workspace_path: String) -> Result<Option<String>, String> {
+    // Empty when HEAD is detached
+    let output = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
+    let branch = output.trim();
+    Ok(if branch.is_empty() { None } else { Some(branch.to_string()) })
+}
+
+#[tauri::command]
+pub async fn git_unpushed_commits(
+    workspace_path: String,
+    remote_name: Option<String>,
+) -> Result<Vec<GitLogEntry>, String> {
+    // Compare against the configured upstream, or the same-named branch on `remote_name`
+    let base = match remote_name {
+        Some(remote) => {
+            let branch = run_git_command(&workspace_path, &["branch", "--show-current"], 5)?;
+            format!("{}/{}", remote, branch.trim())
+        }
+        None => "@{u}".to_string(),
+    };
+
+    // No upstream (or no such remote branch) means nothing to list, as in git_get_status
+    if run_git_command(&workspace_path, &["rev-parse", "--verify", "--quiet", &base], 5).is_err() {
+        return Ok(Vec::new());
+    }
+
+    let range = format!("{}..HEAD", base);
+    let output = run_git_command(&workspace_path, &["log", LOG_ENTRY_FORMAT, &range], 10)?;
+    Ok(parse_log_entries(&output))
+}
+
+/// Abort an in-progress merge, rebase or cherry-pick; returns which one was aborted
+#[tauri::command]
+pub async fn git_abort(workspace_path: String) -> Result<String, String> {
+    ensure_repository(&workspace_path)?;
+
+    let operation = detect_in_progress(&workspace_path)
+        .ok_or_else(|| "No merge, rebase or cherry-pick is in progress".to_string())?;
+
+    run_git_command(&workspace_path, &[operation.command(), "--abort"], 10)?;
+    Ok(operation.command().to_string())
+}
+
+/// Stage resolved files and continue an in-progress merge, rebase or cherry-pick
+#[tauri::command]
+pub async fn git_continue(workspace_path: String) -> Result<GitStatus, String> {
+    ensure_repository(&workspace_path)?;
+
+    let operation = detect_in_progress(&workspace_path)
+        .ok_or_else(|| "No merge, rebase or cherry-pick is in progress".to_string())?;
+
+    // `add -A` would mark files as resolved even with conflict markers still in them
+    let unresolved: Vec<String> = list_conflicted_files(&workspace_path)?
+        .into_iter()
+        .filter(|file| {
+            std::fs::read_to_string(Path::new(&workspace_path).join(file))
+                .map(|content| content.lines().any(|line| line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> ")))
+                .unwrap_or(false)
+        })
+        .collect();
+    if !unresolved.is_empty() {
+        return Err(format!("UNRESOLVED_CONFLICTS: {}", unresolved.join(", ")));
+    }
+
+    run_git_command(&workspace_path, &["add", "-A"], 10)?;
+
+    // Keep the prepared commit message instead of waiting on an editor that never opens.
+    // GIT_EDITOR in the environment outranks core.editor, so it has to be overridden itself.
+    run_git_command_with_env(&workspace_path, &[operation.command(), "--continue"], 30, &[("GIT_EDITOR", "true")])?;
+
+    git_get_status(workspace_path).await
+}
+
+/// Totals of all uncommitted changes (staged and unstaged) against HEAD
+#[tauri::command]
+pub async fn git_diff_summary(workspace_path: String) -> Result<DiffSummary, String> {
+    if !has_commits(&workspace_path) {
+        return Ok(DiffSummary::default());
+    }
+
+    // e.g. " 3 files changed, 10 insertions(+), 2 deletions(-)"; empty when clean,
+    // and either count is omitted when zero
+    let output = run_git_command(&workspace_path, &["diff", "--shortstat", "HEAD"], 10)?;
+    let mut summary = DiffSummary::default();
+    for part in output.trim().split(", ") {
+        let mut words = part.split_whitespace();
+        let count: usize = match words.next().and_then(|n| n.parse().ok()) {
+            Some(count) => count,
+            None => continue,
+        };
+        match words.next() {
+            Some(word) if word.starts_with("file") => summary.files_changed = count,
+            Some(word) if word.starts_with("insertion") => summary.insertions = count,
+            Some(word) if word.starts_with("deletion") => summary.deletions = count,
+            _ => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_porcelain_z_reads_rename_and_copy_sources() {
+        let output = "R  notes/new.md\0notes/old.md\0C  copy.md\0original.md\0 M other.md\0";
+
+        let entries = parse_porcelain_z(output);
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].index, 'R');
+        assert_eq!(entries[0].path, "notes/new.md");
+        assert_eq!(entries[0].orig_path.as_deref(), Some("notes/old.md"));
+
+        assert_eq!(entries[1].index, 'C');
+        assert_eq!(entries[1].path, "copy.md");
+        assert_eq!(entries[1].orig_path.as_deref(), Some("original.md"));
+
+        assert_eq!(entries[2].worktree, 'M');
+        assert_eq!(entries[2].path, "other.md");
+        assert_eq!(entries[2].orig_path, None);
+    }
+
+    #[test]
+    fn summarize_porcelain_reports_renames_by_destination() {
+        let summary = summarize_porcelain("R  notes/new.md\0notes/old.md\0");
+
+        assert_eq!(summary.modified_files, vec!["notes/new.md".to_string()]);
+        assert_eq!(summary.renamed_files.len(), 1);
+        assert_eq!(summary.renamed_files[0].from, "notes/old.md");
+        assert_eq!(summary.renamed_files[0].to, "notes/new.md");
+        assert_eq!(summary.staged_count, 1);
+        assert_eq!(summary.modified_count, 0);
+    }
+
+    #[test]
+    fn validate_git_arg_rejects_options_and_empty_values() {
+        assert!(validate_git_arg("branch name", "feature/notes").is_ok());
+        assert!(validate_git_arg("branch name", "fix-1").is_ok());
+        assert!(validate_git_arg("branch name", "-f").is_err());
+        assert!(validate_git_arg("branch name", "--orphan=x").is_err());
+        assert!(validate_git_arg("config key", "").is_err());
+        assert!(validate_git_arg("config key", "  ").is_err());
+    }
+
+    #[test]
+    fn remove_dir_contents_keeps_the_directory() {
+        let dir = std::env::temp_dir().join(format!("my-kanban-clone-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join(".git/objects")).unwrap();
+        std::fs::write(dir.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+        std::fs::write(dir.join("partial.md"), "").unwrap();
+
+        remove_dir_contents(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn discard_action_covers_each_porcelain_state() {
+        let cases = [
+            ("?? new.md\0", DiscardAction::Delete),
+            ("A  staged-new.md\0", DiscardAction::UnstageAndDelete),
+            ("AM staged-new-edited.md\0", DiscardAction::UnstageAndDelete),
+            ("R  new.md\0old.md\0", DiscardAction::UnstageAndDelete),
+            (" M edited.md\0", DiscardAction::RestoreFromHead),
+            ("M  staged.md\0", DiscardAction::RestoreFromHead),
+            ("MM staged-and-edited.md\0", DiscardAction::RestoreFromHead),
+            (" D deleted.md\0", DiscardAction::RestoreFromHead),
+            ("D  staged-delete.md\0", DiscardAction::RestoreFromHead),
+            ("UU conflicted.md\0", DiscardAction::RestoreFromHead),
+        ];
+
+        for (status, expected) in cases {
+            let entries = parse_porcelain_z(status);
+            assert_eq!(discard_action(&entries[0]), expected, "{:?}", status);
+        }
+    }
+
+    #[test]
+    fn parse_porcelain_z_keeps_spaces_and_non_ascii_verbatim() {
+        let output = " M café note.md\0?? 메모 초안.md\0R  dir/new name.md\0dir/ancien nom.md\0";
+
+        let entries = parse_porcelain_z(output);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].path, "café note.md");
+        assert_eq!(entries[1].path, "메모 초안.md");
+        assert_eq!(entries[2].path, "dir/new name.md");
+        assert_eq!(entries[2].orig_path.as_deref(), Some("dir/ancien nom.md"));
+    }
+}
@@ -1,71 +1,63 @@
-use tauri::{Manager, WebviewUrl, WebviewWindowBuilder, State};
+use std::collections::HashMap;
+use tauri::{Manager, State, WindowEvent};
 
 mod terminal;
-use terminal::TerminalManager;
+use terminal::{SpawnOptions, TerminalInfo, TerminalManager, TerminalMemoryReport, TerminalTranscript};
 
 mod git_shell;
+use git_shell::RemotePollManager;
 
 mod file_watcher;
 use file_watcher::FileWatcherManager;
 
-#[derive(serde::Serialize, serde::Deserialize)]
-struct WindowBounds {
-    x: f64,
-    y: f64,
-    width: f64,
-    height: f64,
-}
+mod notes;
+
+mod search_index;
+use search_index::SearchIndex;
+
+mod side_browser;
+
+mod window_state;
 
+/// Show a file in Finder / Explorer / the desktop file manager, selected where supported
 #[tauri::command]
-async fn open_side_browser(
-    app: tauri::AppHandle,
-    url: String,
-    main_bounds: WindowBounds,
-) -> Result<(), String> {
-    // Close existing side browser if it exists
-    if let Some(window) = app.get_webview_window("side-browser") {
-        let _ = window.close();
-    }
+async fn reveal_in_file_manager(app: tauri::AppHandle, file_path: String) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
 
-    // Calculate position for right half of the screen
-    let x = main_bounds.x + (main_bounds.width / 2.0);
-    let y = main_bounds.y;
-    let width = main_bounds.width / 2.0;
-    let height = main_bounds.height;
-
-    // Create a new webview window for the browser
-    WebviewWindowBuilder::new(
-        &app,
-        "side-browser",
-        WebviewUrl::External(url.parse().map_err(|e| format!("Invalid URL: {}", e))?)
-    )
-    .title("Browser")
-    .position(x, y)
-    .inner_size(width, height)
-    .resizable(false)
-    .decorations(true)
-    .always_on_top(false)
-    .build()
-    .map_err(|e| e.to_string())?;
-
-    Ok(())
-}
-
-#[tauri::command]
-async fn close_side_browser(app: tauri::AppHandle) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window("side-browser") {
-        window.close().map_err(|e| e.to_string())?;
+    if !std::path::Path::new(&file_path).exists() {
+        return Err(format!("File not found: {}", file_path));
     }
-    Ok(())
+    app.opener()
+        .reveal_item_in_dir(&file_path)
+        .map_err(|e| format!("Failed to reveal {}: {}", file_path, e))
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn spawn_terminal(
     app: tauri::AppHandle,
     terminal_manager: State<'_, TerminalManager>,
     working_dir: String,
+    shell: Option<String>,
+    args: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    init_command: Option<String>,
+    scrollback_cap: Option<usize>,
+    terminal_id: Option<String>,
 ) -> Result<String, String> {
-    terminal_manager.spawn_terminal(app, working_dir)
+    let options = SpawnOptions {
+        shell,
+        args: args.unwrap_or_default(),
+        env: env.unwrap_or_default(),
+        cols,
+        rows,
+        init_command,
+        scrollback_cap,
+        terminal_id,
+    };
+    terminal_manager.spawn_terminal(app, working_dir, options)
 }
 
 #[tauri::command]
@@ -73,8 +65,77 @@ async fn write_terminal(
     terminal_manager: State<'_, TerminalManager>,
     session_id: String,
     data: String,
+    normalize_newlines: Option<bool>,
+) -> Result<(), String> {
+    terminal_manager.write_terminal(session_id, data, normalize_newlines.unwrap_or(true))
+}
+
+#[tauri::command]
+async fn write_terminal_bytes(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    terminal_manager.write_terminal_bytes(session_id, &data)
+}
+
+#[tauri::command]
+async fn broadcast_terminal_input(
+    terminal_manager: State<'_, TerminalManager>,
+    session_ids: Vec<String>,
+    data: String,
+) -> Result<Vec<String>, String> {
+    Ok(terminal_manager.broadcast_terminal_input(session_ids, data))
+}
+
+#[tauri::command]
+async fn start_terminal_recording(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<(), String> {
+    terminal_manager.start_terminal_recording(session_id)
+}
+
+#[tauri::command]
+async fn stop_terminal_recording(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<TerminalTranscript, String> {
+    terminal_manager.stop_terminal_recording(session_id)
+}
+
+#[tauri::command]
+async fn replay_terminal(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+    transcript: TerminalTranscript,
+) -> Result<(), String> {
+    terminal_manager.replay_terminal(session_id, transcript)
+}
+
+#[tauri::command]
+async fn get_terminal_cwd(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<String, String> {
+    terminal_manager.get_terminal_cwd(session_id)
+}
+
+#[tauri::command]
+async fn get_terminal_scrollback(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<String, String> {
+    terminal_manager.get_terminal_scrollback(session_id)
+}
+
+#[tauri::command]
+async fn clear_terminal(
+    app: tauri::AppHandle,
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
 ) -> Result<(), String> {
-    terminal_manager.write_terminal(session_id, data)
+    terminal_manager.clear_terminal(app, session_id)
 }
 
 #[tauri::command]
@@ -87,6 +148,15 @@ async fn resize_terminal(
     terminal_manager.resize_terminal(session_id, cols, rows)
 }
 
+#[tauri::command]
+async fn resize_all_terminals(
+    terminal_manager: State<'_, TerminalManager>,
+    cols: u16,
+    rows: u16,
+) -> Result<Vec<String>, String> {
+    terminal_manager.resize_all_terminals(cols, rows)
+}
+
 #[tauri::command]
 async fn close_terminal(
     terminal_manager: State<'_, TerminalManager>,
@@ -95,6 +165,82 @@ async fn close_terminal(
     terminal_manager.close_terminal(session_id)
 }
 
+#[tauri::command]
+async fn is_terminal_alive(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<bool, String> {
+    Ok(terminal_manager.is_terminal_alive(session_id))
+}
+
+#[tauri::command]
+async fn signal_terminal(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+    signal: String,
+) -> Result<(), String> {
+    terminal_manager.signal_terminal(session_id, signal)
+}
+
+#[tauri::command]
+async fn get_terminal_exit_code(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<Option<i32>, String> {
+    Ok(terminal_manager.get_terminal_exit_code(session_id))
+}
+
+#[tauri::command]
+async fn list_terminals(
+    terminal_manager: State<'_, TerminalManager>,
+) -> Result<Vec<TerminalInfo>, String> {
+    Ok(terminal_manager.list_terminals())
+}
+
+#[tauri::command]
+async fn set_terminal_note(
+    app: tauri::AppHandle,
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+    text: String,
+) -> Result<(), String> {
+    terminal_manager.set_terminal_note(&app, session_id, text)
+}
+
+#[tauri::command]
+async fn get_terminal_note(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+) -> Result<Option<String>, String> {
+    terminal_manager.get_terminal_note(session_id)
+}
+
+#[tauri::command]
+async fn watch_terminal_pattern(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+    regex: String,
+    event_name: String,
+) -> Result<String, String> {
+    terminal_manager.watch_terminal_pattern(session_id, regex, event_name)
+}
+
+#[tauri::command]
+async fn unwatch_terminal_pattern(
+    terminal_manager: State<'_, TerminalManager>,
+    session_id: String,
+    watch_id: String,
+) -> Result<(), String> {
+    terminal_manager.unwatch_terminal_pattern(session_id, watch_id)
+}
+
+#[tauri::command]
+async fn terminal_memory_report(
+    terminal_manager: State<'_, TerminalManager>,
+) -> Result<TerminalMemoryReport, String> {
+    Ok(terminal_manager.memory_report())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -103,24 +249,123 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .manage(TerminalManager::new())
         .manage(FileWatcherManager::new())
+        .manage(RemotePollManager::new())
+        .manage(SearchIndex::new())
+        .setup(|app| {
+            window_state::restore_main_window(app.handle());
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // Closing the main window shuts the app down: don't leave side browsers,
+            // shells, watchers or remote pollers behind
+            if window.label() == window_state::MAIN_WINDOW_LABEL {
+                if let WindowEvent::CloseRequested { .. } = event {
+                    window_state::save_main_window(window);
+                    side_browser::close_all_side_browsers(window.app_handle());
+                    window.state::<TerminalManager>().close_all();
+                    window.state::<FileWatcherManager>().unwatch_all();
+                    window.state::<RemotePollManager>().stop_all();
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
-            open_side_browser,
-            close_side_browser,
+            side_browser::open_side_browser,
+            side_browser::navigate_side_browser,
+            side_browser::reload_side_browser,
+            side_browser::side_browser_back,
+            side_browser::side_browser_forward,
+            side_browser::set_side_browser_zoom,
+            side_browser::save_side_browser_bounds,
+            side_browser::close_side_browser,
+            window_state::save_window_bounds,
+            window_state::restore_window_bounds,
+            reveal_in_file_manager,
             spawn_terminal,
             write_terminal,
+            write_terminal_bytes,
+            broadcast_terminal_input,
+            start_terminal_recording,
+            stop_terminal_recording,
+            replay_terminal,
+            clear_terminal,
+            get_terminal_scrollback,
+            get_terminal_cwd,
             resize_terminal,
+            resize_all_terminals,
             close_terminal,
+            list_terminals,
+            get_terminal_exit_code,
+            is_terminal_alive,
+            signal_terminal,
+            terminal_memory_report,
+            set_terminal_note,
+            get_terminal_note,
+            watch_terminal_pattern,
+            unwatch_terminal_pattern,
             git_shell::git_get_status,
             git_shell::git_commit,
+            git_shell::git_commit_files,
             git_shell::git_push,
             git_shell::git_pull,
             git_shell::git_sync,
             git_shell::git_is_repository,
             git_shell::git_initialize,
             git_shell::git_lfs_available,
+            git_shell::git_version,
+            git_shell::git_check_environment,
+            git_shell::generate_note_changelog,
+            git_shell::git_worktree_add,
+            git_shell::git_worktree_list,
+            git_shell::git_worktree_remove,
+            git_shell::start_remote_poll,
+            git_shell::stop_remote_poll,
+            git_shell::git_squash_last,
+            git_shell::git_checkout_file_from,
+            git_shell::git_log,
+            git_shell::git_diff,
+            git_shell::git_branch_list,
+            git_shell::git_checkout,
+            git_shell::git_stash,
+            git_shell::git_stash_pop,
+            git_shell::git_stash_list,
+            git_shell::git_stage_file,
+            git_shell::git_unstage_file,
+            git_shell::git_discard_file,
+            git_shell::git_fetch,
+            git_shell::git_remote_list,
+            git_shell::git_config_get,
+            git_shell::git_config_set,
+            git_shell::git_clone,
+            git_shell::git_conflicts,
+            git_shell::git_reset,
+            git_shell::git_show,
+            git_shell::git_blame,
+            git_shell::git_delete_branch,
+            git_shell::git_lfs_migrate,
+            git_shell::git_gitignore_add,
+            git_shell::git_has_changes,
+            git_shell::git_current_branch,
+            git_shell::git_unpushed_commits,
+            git_shell::git_abort,
+            git_shell::git_continue,
+            git_shell::git_diff_summary,
             file_watcher::watch_file,
             file_watcher::unwatch_file,
             file_watcher::watch_workspace,
+            file_watcher::unwatch_workspace,
+            file_watcher::list_watched,
+            file_watcher::workspace_exists,
+            notes::resolve_wikilink,
+            notes::normalize_indentation,
+            notes::get_note_outline,
+            notes::get_note_line_stats,
+            notes::save_note_atomic,
+            notes::save_file_atomic,
+            notes::find_case_collisions,
+            notes::resolve_case_collision,
+            notes::list_workspace_tree_with_status,
+            search_index::build_search_index,
+            search_index::query_index,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,1159 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+use uuid::Uuid;
+
+use crate::git_shell::{parse_porcelain_z, resolve_workspace_path, run_git_command};
+
+/// Directories that never contain pages and are skipped when walking the workspace
+const SKIPPED_DIRS: &[&str] = &[".git", ".images", "node_modules"];
+
+/// Frontmatter and the first heading live at the top of a note; never read more than
+/// this when only the note's identity is needed (guards against huge pasted lines)
+const IDENTITY_READ_LIMIT: u64 = 64 * 1024;
+
+/// Lines longer than this make line-based editors and parsers sluggish
+const DEGENERATE_LINE_LENGTH: usize = 10_000;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WikilinkResolution {
+    /// Absolute path of the target when exactly one note matched
+    pub resolved: Option<String>,
+    /// All matching notes when the link is ambiguous (empty when resolved or not found)
+    pub candidates: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineStats {
+    pub byte_size: u64,
+    pub line_count: usize,
+    /// Longest line in bytes
+    pub max_line_length: usize,
+    /// 1-based line number of the longest line
+    pub max_line_number: usize,
+    /// True when the longest line exceeds `DEGENERATE_LINE_LENGTH`
+    pub is_degenerate: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OutlineNode {
+    pub level: u8,
+    pub text: String,
+    /// 1-based line number of the heading (the text line for setext headings)
+    pub line: usize,
+    pub children: Vec<OutlineNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaseCollisionEntry {
+    /// Workspace-relative path with `/` separators
+    pub path: String,
+    pub on_disk: bool,
+    pub tracked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaseCollision {
+    /// Lowercased path shared by every entry
+    pub key: String,
+    pub entries: Vec<CaseCollisionEntry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileGitStatus {
+    Clean,
+    Modified,
+    Staged,
+    Untracked,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TreeNode {
+    pub name: String,
+    /// Workspace-relative path with `/` separators
+    pub path: String,
+    pub is_dir: bool,
+    /// For directories: `Modified` when anything beneath them is not clean
+    pub status: FileGitStatus,
+    pub children: Vec<TreeNode>,
+}
+
+/// Recursively collect every `.md` file under `root`, skipping hidden and tooling directories
+pub(crate) fn collect_markdown_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if path.is_dir() {
+                if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str()) {
+                    stack.push(path);
+                }
+            } else if path.extension().map(|ext| ext == "md").unwrap_or(false) {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    files
+}
+
+/// Read at most `limit` bytes from the start of a file
+fn read_file_prefix(path: &Path, limit: u64) -> std::io::Result<String> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.take(limit).read_to_end(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Normalize text for comparisons: NFC (handles macOS NFD filenames), trimmed, lowercase
+fn normalize_key(text: &str) -> String {
+    text.trim().nfc().collect::<String>().to_lowercase()
+}
+
+/// Strip surrounding quotes from a YAML scalar value
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    if value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+    {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+/// Identity of a page as far as wikilinks are concerned
+pub(crate) struct NoteIdentity {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub heading: Option<String>,
+}
+
+/// Read the frontmatter `id`/`title` and the first H1 heading of a note
+pub(crate) fn read_note_identity(content: &str) -> NoteIdentity {
+    let mut identity = NoteIdentity {
+        id: None,
+        title: None,
+        heading: None,
+    };
+
+    let mut lines = content.lines().peekable();
+
+    // Frontmatter block: ---\n key: value \n---
+    if lines.peek().map(|l| l.trim_end() == "---").unwrap_or(false) {
+        lines.next();
+        for line in lines.by_ref() {
+            if line.trim_end() == "---" {
+                break;
+            }
+            if let Some((key, value)) = line.split_once(':') {
+                match key.trim() {
+                    "id" => identity.id = Some(unquote(value).to_string()),
+                    "title" => identity.title = Some(unquote(value).to_string()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // First H1 outside of code fences
+    let mut fence: Option<CodeFence> = None;
+    for line in lines {
+        match fence {
+            Some(open) => {
+                if open.is_closed_by(line) {
+                    fence = None;
+                }
+            }
+            None => {
+                if let Some((open, _)) = parse_code_fence(line) {
+                    fence = Some(open);
+                } else if let Some(heading) = line.trim_start().strip_prefix("# ") {
+                    identity.heading = Some(heading.trim().to_string());
+                    break;
+                }
+            }
+        }
+    }
+
+    identity
+}
+
+/// Resolve the target of `[[link_text]]` within the workspace.
+///
+/// Accepts `[[Title]]`, `[[page-id|Display Text]]` and `[[Title#Heading]]` forms.
+/// Matching is case-insensitive and tried in order of precedence: page id, filename,
+/// then frontmatter title / first H1. The first tier that yields any match wins; if
+/// that tier has several matches the link is ambiguous and all of them are returned.
+fn resolve_wikilink_in(workspace_path: &str, link_text: &str) -> WikilinkResolution {
+    // Drop display alias and heading anchor
+    let target = link_text.split('|').next().unwrap_or("");
+    let target = target.split('#').next().unwrap_or("");
+    let target = target.trim();
+    let target = target.strip_suffix(".md").unwrap_or(target);
+    let key = normalize_key(target);
+
+    if key.is_empty() {
+        return WikilinkResolution {
+            resolved: None,
+            candidates: Vec::new(),
+        };
+    }
+
+    let mut by_id = Vec::new();
+    let mut by_filename = Vec::new();
+    let mut by_title = Vec::new();
+
+    for path in collect_markdown_files(Path::new(workspace_path)) {
+        let path_str = path.to_string_lossy().to_string();
+
+        let stem_matches = path
+            .file_stem()
+            .map(|stem| normalize_key(&stem.to_string_lossy()) == key)
+            .unwrap_or(false);
+        if stem_matches {
+            by_filename.push(path_str.clone());
+        }
+
+        let content = match read_file_prefix(&path, IDENTITY_READ_LIMIT) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        let identity = read_note_identity(&content);
+
+        if identity.id.as_deref().map(normalize_key).as_deref() == Some(key.as_str()) {
+            by_id.push(path_str.clone());
+        }
+
+        let title_matches = [identity.title.as_deref(), identity.heading.as_deref()]
+            .iter()
+            .flatten()
+            .any(|title| normalize_key(title) == key);
+        if title_matches && !stem_matches {
+            by_title.push(path_str);
+        }
+    }
+
+    let matches = [by_id, by_filename, by_title]
+        .into_iter()
+        .find(|tier| !tier.is_empty())
+        .unwrap_or_default();
+
+    match matches.len() {
+        1 => WikilinkResolution {
+            resolved: matches.into_iter().next(),
+            candidates: Vec::new(),
+        },
+        _ => WikilinkResolution {
+            resolved: None,
+            candidates: matches,
+        },
+    }
+}
+
+#[tauri::command]
+pub async fn resolve_wikilink(
+    workspace_path: String,
+    link_text: String,
+) -> Result<WikilinkResolution, String> {
+    if !Path::new(&workspace_path).is_dir() {
+        return Err(format!("Workspace not found: {}", workspace_path));
+    }
+    Ok(resolve_wikilink_in(&workspace_path, &link_text))
+}
+
+/// Fence languages where literal tabs are significant and must be left untouched
+const TAB_SIGNIFICANT_LANGS: &[&str] = &["make", "makefile", "mk", "go", "tsv"];
+
+/// Rewrite the leading whitespace of a single line using tabs or spaces
+fn reindent_line(line: &str, use_spaces: bool, width: usize) -> String {
+    let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+    let (indent, rest) = line.split_at(indent_len);
+
+    // Visual column of the first non-whitespace character
+    let mut columns = 0;
+    for ch in indent.chars() {
+        if ch == '\t' {
+            columns += width - (columns % width);
+        } else {
+            columns += 1;
+        }
+    }
+
+    let new_indent = if use_spaces {
+        " ".repeat(columns)
+    } else {
+        format!("{}{}", "\t".repeat(columns / width), " ".repeat(columns % width))
+    };
+
+    format!("{}{}", new_indent, rest)
+}
+
+/// A code fence line: a run of three or more backticks or tildes, indented at most three
+/// spaces
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CodeFence {
+    ch: char,
+    len: usize,
+}
+
+/// Parse a fence line, returning the fence and its info string (e.g. the language)
+fn parse_code_fence(line: &str) -> Option<(CodeFence, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+    let ch = rest.chars().next().filter(|&c| c == '`' || c == '~')?;
+    let len = rest.chars().take_while(|&c| c == ch).count();
+    if len < 3 {
+        return None;
+    }
+    Some((CodeFence { ch, len }, rest[len..].trim()))
+}
+
+impl CodeFence {
+    /// A closing fence uses the same character, is at least as long, and has no info string
+    fn is_closed_by(&self, line: &str) -> bool {
+        match parse_code_fence(line) {
+            Some((close, info)) => close.ch == self.ch && close.len >= self.len && info.is_empty(),
+            None => false,
+        }
+    }
+}
+
+/// Convert indentation throughout a note to tabs or `width`-wide spaces.
+///
+/// Code fences are reindented like the rest of the note, except for languages where
+/// tabs carry meaning (Makefiles, Go, TSV), which are passed through verbatim. With
+/// `preserve_code_blocks`, every fenced block is left verbatim.
+#[tauri::command]
+pub async fn normalize_indentation(
+    content: String,
+    use_spaces: bool,
+    width: u8,
+    preserve_code_blocks: Option<bool>,
+) -> Result<String, String> {
+    if width == 0 {
+        return Err("Indentation width must be greater than zero".to_string());
+    }
+    Ok(normalize_indentation_in(
+        &content,
+        use_spaces,
+        width as usize,
+        preserve_code_blocks.unwrap_or(false),
+    ))
+}
+
+fn normalize_indentation_in(content: &str, use_spaces: bool, width: usize, preserve_code_blocks: bool) -> String {
+    let mut result = String::with_capacity(content.len());
+    // Some(fence, preserve) while inside a fenced code block
+    let mut fence: Option<(CodeFence, bool)> = None;
+
+    for line in content.split_inclusive('\n') {
+        let (body, ending) = match line.strip_suffix('\n') {
+            Some(body) => (body, "\n"),
+            None => (line, ""),
+        };
+
+        let preserve = match fence {
+            // Closing fence
+            Some((open, _)) if open.is_closed_by(body) => {
+                fence = None;
+                false
+            }
+            Some((_, preserve)) => preserve,
+            // Opening fence: remember whether its contents must keep literal tabs
+            None => {
+                if let Some((open, info)) = parse_code_fence(body) {
+                    let lang = info.split_whitespace().next().unwrap_or("").to_lowercase();
+                    let preserve = preserve_code_blocks || TAB_SIGNIFICANT_LANGS.contains(&lang.as_str());
+                    fence = Some((open, preserve));
+                }
+                false
+            }
+        };
+
+        if preserve {
+            result.push_str(body);
+        } else {
+            result.push_str(&reindent_line(body, use_spaces, width));
+        }
+        result.push_str(ending);
+    }
+
+    result
+}
+
+/// Parse an ATX heading (`## Title ##`), returning its level and text
+fn parse_atx_heading(line: &str) -> Option<(u8, String)> {
+    // Up to three spaces of indentation are allowed
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    if indent > 3 {
+        return None;
+    }
+    let rest = &line[indent..];
+
+    let level = rest.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &rest[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None; // "#hashtag", not a heading
+    }
+
+    // Drop an optional closing sequence of #s
+    let text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    let text = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        text
+    };
+
+    Some((level as u8, text.to_string()))
+}
+
+/// Setext underline level: `===` is 1, `---` is 2
+fn parse_setext_underline(line: &str) -> Option<u8> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || line.len() - line.trim_start().len() > 3 {
+        return None;
+    }
+    if trimmed.chars().all(|c| c == '=') {
+        Some(1)
+    } else if trimmed.chars().all(|c| c == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Whether a line is plain paragraph text, the only thing a setext underline can follow.
+/// List items, block quotes, thematic breaks and indented code are not.
+fn is_paragraph_text(line: &str) -> bool {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    let trimmed = line.trim();
+    if indent > 3 || trimmed.is_empty() || trimmed.starts_with('>') {
+        return false;
+    }
+
+    // Bullet list item: "-", "*" or "+" followed by a space (or nothing)
+    let mut chars = trimmed.chars();
+    if let Some('-' | '*' | '+') = chars.next() {
+        if matches!(chars.next(), None | Some(' ') | Some('\t')) {
+            return false;
+        }
+    }
+
+    // Ordered list item: up to nine digits, then "." or ")"
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if (1..=9).contains(&digits) {
+        let rest = &trimmed[digits..];
+        if (rest.starts_with('.') || rest.starts_with(')')) && matches!(rest[1..].chars().next(), None | Some(' ') | Some('\t')) {
+            return false;
+        }
+    }
+
+    // Thematic break: three or more of the same "-", "*" or "_", spaces allowed
+    let marks: String = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    let is_break = marks.len() >= 3
+        && ['-', '*', '_'].iter().any(|&mark| marks.chars().all(|c| c == mark));
+    !is_break
+}
+
+/// Nest a flat list of headings by level
+fn build_outline(headings: Vec<(u8, String, usize)>) -> Vec<OutlineNode> {
+    let mut roots: Vec<OutlineNode> = Vec::new();
+    // Chain of currently open headings, each a child of the previous
+    let mut stack: Vec<OutlineNode> = Vec::new();
+
+    fn attach(node: OutlineNode, stack: &mut [OutlineNode], roots: &mut Vec<OutlineNode>) {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+
+    for (level, text, line) in headings {
+        while stack.last().map(|open| open.level >= level).unwrap_or(false) {
+            let closed = stack.pop().unwrap();
+            attach(closed, &mut stack, &mut roots);
+        }
+        stack.push(OutlineNode {
+            level,
+            text,
+            line,
+            children: Vec::new(),
+        });
+    }
+
+    while let Some(closed) = stack.pop() {
+        attach(closed, &mut stack, &mut roots);
+    }
+
+    roots
+}
+
+/// Parse a note's headings into a nested outline.
+///
+/// Handles ATX (`#`) and setext (`===` / `---` underline) headings, and ignores
+/// frontmatter and anything inside fenced code blocks.
+#[tauri::command]
+pub async fn get_note_outline(content: String) -> Result<Vec<OutlineNode>, String> {
+    Ok(parse_outline(&content))
+}
+
+fn parse_outline(content: &str) -> Vec<OutlineNode> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut headings = Vec::new();
+    let mut index = 0;
+
+    // Skip the frontmatter block
+    if lines.first().map(|l| l.trim_end() == "---").unwrap_or(false) {
+        if let Some(end) = lines.iter().skip(1).position(|l| l.trim_end() == "---") {
+            index = end + 2;
+        }
+    }
+
+    let mut fence: Option<CodeFence> = None;
+    // Previous line if it could be the text of a setext heading
+    let mut paragraph_line: Option<(usize, &str)> = None;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+        let line_number = index + 1;
+        index += 1;
+
+        match fence {
+            Some(open) => {
+                if open.is_closed_by(line) {
+                    fence = None;
+                }
+                continue;
+            }
+            None => {
+                if let Some((open, _)) = parse_code_fence(line) {
+                    fence = Some(open);
+                    paragraph_line = None;
+                    continue;
+                }
+            }
+        }
+
+        if trimmed.is_empty() {
+            paragraph_line = None;
+            continue;
+        }
+
+        if let Some((level, text)) = parse_atx_heading(line) {
+            headings.push((level, text, line_number));
+            paragraph_line = None;
+            continue;
+        }
+
+        if let (Some((text_line, text)), Some(level)) = (paragraph_line, parse_setext_underline(line)) {
+            headings.push((level, text.trim().to_string(), text_line));
+            paragraph_line = None;
+            continue;
+        }
+
+        // Only paragraph text can become a setext heading's text
+        paragraph_line = if is_paragraph_text(line) {
+            Some((line_number, line))
+        } else {
+            None
+        };
+    }
+
+    build_outline(headings)
+}
+
+/// Scan a file for line count and longest line without materializing lines, so
+/// a note that is one giant line costs no more than a normal one.
+fn scan_line_stats(path: &Path) -> std::io::Result<LineStats> {
+    let mut file = fs::File::open(path)?;
+    let mut buf = [0u8; 64 * 1024];
+
+    let mut byte_size = 0u64;
+    let mut line_count = 0;
+    let mut current_length = 0;
+    let mut max_line_length = 0;
+    let mut max_line_number = 0;
+
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        byte_size += n as u64;
+
+        for &byte in &buf[..n] {
+            if byte == b'\n' {
+                line_count += 1;
+                if current_length > max_line_length {
+                    max_line_length = current_length;
+                    max_line_number = line_count;
+                }
+                current_length = 0;
+            } else if byte != b'\r' {
+                current_length += 1;
+            }
+        }
+    }
+
+    // Trailing line without a newline
+    if current_length > 0 {
+        line_count += 1;
+        if current_length > max_line_length {
+            max_line_length = current_length;
+            max_line_number = line_count;
+        }
+    }
+
+    Ok(LineStats {
+        byte_size,
+        line_count,
+        max_line_length,
+        max_line_number,
+        is_degenerate: max_line_length > DEGENERATE_LINE_LENGTH,
+    })
+}
+
+#[tauri::command]
+pub async fn get_note_line_stats(workspace_path: String, file_rel: String) -> Result<LineStats, String> {
+    let path = resolve_workspace_path(&workspace_path, &file_rel)?;
+    scan_line_stats(&path).map_err(|e| format!("Failed to read {}: {}", file_rel, e))
+}
+
+/// Durably replace `target` with `content`: write a temp file in the same directory,
+/// fsync it, rename it over the target, then fsync the directory (Unix) so the rename
+/// itself survives a crash. Readers only ever see the old or the new file in full.
+pub(crate) fn write_atomic(target: &Path, content: &[u8]) -> std::io::Result<()> {
+    let dir = target
+        .parent()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "File has no parent directory"))?;
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Invalid file path"))?
+        .to_string_lossy()
+        .to_string();
+
+    // Hidden and without a .md extension, so the watchers ignore the temp file itself
+    let temp_path = dir.join(format!(".{}.{}.tmp", file_name, Uuid::new_v4()));
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+
+        // Keep the original file's permissions
+        if let Ok(metadata) = fs::metadata(target) {
+            fs::set_permissions(&temp_path, metadata.permissions())?;
+        }
+
+        fs::rename(&temp_path, target)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    #[cfg(unix)]
+    fs::File::open(dir)?.sync_all()?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn save_note_atomic(workspace_path: String, file_rel: String, content: String) -> Result<(), String> {
+    let path = resolve_workspace_path(&workspace_path, &file_rel)?;
+    write_atomic(&path, content.as_bytes()).map_err(|e| format!("Failed to save {}: {}", file_rel, e))
+}
+
+/// Whether `target` lies inside `root` once symlinks and `..` are resolved. The target
+/// itself may not exist yet, so its parent directory is what gets resolved.
+fn is_within_workspace(root: &Path, target: &Path) -> bool {
+    let (parent, file_name) = match (target.parent(), target.file_name()) {
+        (Some(parent), Some(file_name)) => (parent, file_name),
+        _ => return false,
+    };
+    match (root.canonicalize(), parent.canonicalize()) {
+        (Ok(root), Ok(parent)) => parent.join(file_name).starts_with(root),
+        _ => false,
+    }
+}
+
+/// Save a file by absolute path with `write_atomic`, so `watch_file` never sees it
+/// half-written. The path must be inside the open workspace.
+#[tauri::command]
+pub async fn save_file_atomic(workspace_path: String, path: String, contents: String) -> Result<(), String> {
+    let target = Path::new(&path);
+    if !is_within_workspace(Path::new(&workspace_path), target) {
+        return Err(format!("Path escapes the workspace: {}", path));
+    }
+    write_atomic(target, contents.as_bytes()).map_err(|e| format!("Failed to save {}: {}", path, e))
+}
+
+/// Every file under `root` as a workspace-relative, `/`-separated NFC path
+fn collect_relative_files(root: &Path) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if path.is_dir() {
+                if name != ".git" && name != "node_modules" {
+                    stack.push(path);
+                }
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                let relative: Vec<String> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().nfc().collect())
+                    .collect();
+                files.push(relative.join("/"));
+            }
+        }
+    }
+
+    files
+}
+
+/// Find paths that differ only by case, across both the working tree and the git index.
+///
+/// On a case-insensitive filesystem (macOS) only one of `Note.md`/`note.md` can exist on
+/// disk, but git on Linux happily tracks both; a sync then silently collapses them.
+#[tauri::command]
+pub async fn find_case_collisions(workspace_path: String) -> Result<Vec<CaseCollision>, String> {
+    let root = Path::new(&workspace_path);
+    if !root.is_dir() {
+        return Err(format!("Workspace not found: {}", workspace_path));
+    }
+
+    // exact path -> (on_disk, tracked)
+    let mut paths: BTreeMap<String, (bool, bool)> = BTreeMap::new();
+
+    for path in collect_relative_files(root) {
+        paths.entry(path).or_default().0 = true;
+    }
+
+    // Not being a repository is fine - only on-disk names are compared then
+    if let Ok(output) = run_git_command(&workspace_path, &["ls-files", "-z"], 10) {
+        for path in output.split('\0').filter(|p| !p.is_empty()) {
+            let path: String = path.nfc().collect();
+            paths.entry(path).or_default().1 = true;
+        }
+    }
+
+    let mut groups: BTreeMap<String, Vec<CaseCollisionEntry>> = BTreeMap::new();
+    for (path, (on_disk, tracked)) in paths {
+        groups.entry(path.to_lowercase()).or_default().push(CaseCollisionEntry {
+            path,
+            on_disk,
+            tracked,
+        });
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|(_, entries)| entries.len() > 1)
+        .map(|(key, entries)| CaseCollision { key, entries })
+        .collect())
+}
+
+/// Rename one side of a case collision to a distinct name. Tracked files are moved
+/// with `git mv` so the index follows; untracked files are renamed on disk.
+#[tauri::command]
+pub async fn resolve_case_collision(
+    workspace_path: String,
+    from_rel: String,
+    to_rel: String,
+) -> Result<(), String> {
+    let from_path = resolve_workspace_path(&workspace_path, &from_rel)?;
+    let to_path = resolve_workspace_path(&workspace_path, &to_rel)?;
+
+    if from_rel.to_lowercase() == to_rel.to_lowercase() {
+        return Err("New name must differ by more than letter case".to_string());
+    }
+    if to_path.exists() {
+        return Err(format!("{} already exists", to_rel));
+    }
+
+    let is_tracked = run_git_command(&workspace_path, &["ls-files", "--error-unmatch", "--", &from_rel], 5).is_ok();
+    if is_tracked {
+        run_git_command(&workspace_path, &["mv", "--", &from_rel, &to_rel], 10)?;
+    } else {
+        fs::rename(&from_path, &to_path)
+            .map_err(|e| format!("Failed to rename {}: {}", from_rel, e))?;
+    }
+
+    Ok(())
+}
+
+/// Walk `dir` into tree nodes, annotating files from the porcelain status map
+fn build_status_tree(root: &Path, dir: &Path, statuses: &HashMap<String, FileGitStatus>) -> Vec<TreeNode> {
+    let mut nodes = Vec::new();
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return nodes,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name: String = entry.file_name().to_string_lossy().nfc().collect();
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+
+        let relative: Vec<String> = match path.strip_prefix(root) {
+            Ok(relative) => relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().nfc().collect())
+                .collect(),
+            Err(_) => continue,
+        };
+        let relative = relative.join("/");
+
+        if path.is_dir() {
+            let children = build_status_tree(root, &path, statuses);
+            let dirty = children.iter().any(|child| child.status != FileGitStatus::Clean);
+            nodes.push(TreeNode {
+                name,
+                path: relative,
+                is_dir: true,
+                status: if dirty { FileGitStatus::Modified } else { FileGitStatus::Clean },
+                children,
+            });
+        } else {
+            let status = statuses.get(&relative).copied().unwrap_or(FileGitStatus::Clean);
+            nodes.push(TreeNode {
+                name,
+                path: relative,
+                is_dir: false,
+                status,
+                children: Vec::new(),
+            });
+        }
+    }
+
+    // Directories first, then alphabetical
+    nodes.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    nodes
+}
+
+/// Map porcelain output to per-file statuses keyed by workspace-relative path. Porcelain
+/// paths are relative to the repository root, so `prefix` (`git rev-parse --show-prefix`,
+/// e.g. `notes/`) is stripped and entries outside the workspace are dropped.
+fn porcelain_statuses(output: &str, prefix: &str) -> HashMap<String, FileGitStatus> {
+    let mut statuses = HashMap::new();
+    for entry in parse_porcelain_z(output) {
+        let path: String = entry.path.nfc().collect();
+        let relative = match path.strip_prefix(prefix) {
+            Some(relative) => relative.to_string(),
+            None => continue,
+        };
+
+        let status = if entry.index == '?' {
+            FileGitStatus::Untracked
+        } else if entry.worktree != ' ' {
+            FileGitStatus::Modified
+        } else {
+            FileGitStatus::Staged
+        };
+        statuses.insert(relative, status);
+    }
+    statuses
+}
+
+/// List the workspace tree with each file's git status from a single porcelain parse
+#[tauri::command]
+pub async fn list_workspace_tree_with_status(workspace_path: String) -> Result<Vec<TreeNode>, String> {
+    let root = Path::new(&workspace_path);
+    if !root.is_dir() {
+        return Err(format!("Workspace not found: {}", workspace_path));
+    }
+
+    // Outside a repository every file is simply reported as clean
+    let mut statuses: HashMap<String, FileGitStatus> = HashMap::new();
+    if let Ok(output) = run_git_command(
+        &workspace_path,
+        &["status", "--porcelain", "-z", "--untracked-files=all"],
+        5,
+    ) {
+        // Non-empty when the workspace is a subdirectory of the repository
+        let prefix = run_git_command(&workspace_path, &["rev-parse", "--show-prefix"], 5)?;
+        let prefix: String = prefix.trim_end_matches(['\r', '\n']).nfc().collect();
+        statuses = porcelain_statuses(&output, &prefix);
+    }
+
+    Ok(build_status_tree(root, root, &statuses))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_within_workspace_rejects_paths_outside_the_root() {
+        let root = std::env::temp_dir().join(format!("my-kanban-notes-{}", Uuid::new_v4()));
+        fs::create_dir_all(root.join("sub")).unwrap();
+
+        assert!(is_within_workspace(&root, &root.join("page.md")));
+        assert!(is_within_workspace(&root, &root.join("sub/page.md")));
+        assert!(!is_within_workspace(&root, &root.join("../outside.md")));
+        assert!(!is_within_workspace(&root, &root.join("sub/../../outside.md")));
+        assert!(!is_within_workspace(&root, &std::env::temp_dir().join("outside.md")));
+        // A directory that doesn't exist can't be checked, so it's refused
+        assert!(!is_within_workspace(&root, &root.join("missing/page.md")));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Temporary workspace holding the given (relative path, content) notes
+    fn workspace_with(notes: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("my-kanban-links-{}", Uuid::new_v4()));
+        for (path, content) in notes {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    fn resolved_name(resolution: &WikilinkResolution) -> Option<String> {
+        resolution
+            .resolved
+            .as_deref()
+            .map(|path| Path::new(path).file_name().unwrap().to_string_lossy().to_string())
+    }
+
+    #[test]
+    fn resolve_wikilink_prefers_id_then_filename_then_title() {
+        let root = workspace_with(&[
+            ("by-id.md", "---\nid: plan\ntitle: Something Else\n---\n"),
+            ("plan.md", "# Unrelated\n"),
+            ("titled.md", "---\ntitle: Project Plan\n---\n"),
+            ("heading.md", "# Weekly Review\n"),
+        ]);
+        let workspace = root.to_string_lossy().to_string();
+
+        // An id beats a filename with the same text
+        assert_eq!(resolved_name(&resolve_wikilink_in(&workspace, "plan")), Some("by-id.md".to_string()));
+        // Exact and case-insensitive titles, from frontmatter or the first H1
+        assert_eq!(
+            resolved_name(&resolve_wikilink_in(&workspace, "Project Plan")),
+            Some("titled.md".to_string())
+        );
+        assert_eq!(
+            resolved_name(&resolve_wikilink_in(&workspace, "project plan")),
+            Some("titled.md".to_string())
+        );
+        assert_eq!(
+            resolved_name(&resolve_wikilink_in(&workspace, "WEEKLY review")),
+            Some("heading.md".to_string())
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_wikilink_strips_alias_heading_and_one_md_suffix() {
+        let root = workspace_with(&[("Daily.md", "# Daily\n"), ("a.md.md", "")]);
+        let workspace = root.to_string_lossy().to_string();
+
+        for link in ["Daily#Morning", "Daily.md#Morning", "daily|Today", "Daily#Morning|Today"] {
+            assert_eq!(resolved_name(&resolve_wikilink_in(&workspace, link)), Some("Daily.md".to_string()), "{}", link);
+        }
+        // Only one ".md" is stripped, so "a.md.md" still names the file stem "a.md"
+        assert_eq!(resolved_name(&resolve_wikilink_in(&workspace, "a.md.md")), Some("a.md.md".to_string()));
+        assert!(resolve_wikilink_in(&workspace, "#Morning").resolved.is_none());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resolve_wikilink_reports_ambiguous_matches_as_candidates() {
+        let root = workspace_with(&[
+            ("one/Meeting.md", ""),
+            ("two/meeting.md", ""),
+            ("notes.md", "# Meeting\n"),
+        ]);
+        let workspace = root.to_string_lossy().to_string();
+
+        // Both filenames match; the title match is in a lower tier and ignored
+        let resolution = resolve_wikilink_in(&workspace, "meeting");
+        assert!(resolution.resolved.is_none());
+        let mut candidates: Vec<String> = resolution
+            .candidates
+            .iter()
+            .map(|path| Path::new(path).strip_prefix(&root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        candidates.sort();
+        assert_eq!(candidates, vec!["one/Meeting.md".to_string(), "two/meeting.md".to_string()]);
+
+        assert!(resolve_wikilink_in(&workspace, "missing").candidates.is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn normalize_indentation_converts_outside_and_inside_fences() {
+        let content = "\titem\n```js\n\tcode();\n```\n";
+        assert_eq!(
+            normalize_indentation_in(content, true, 4, false),
+            "    item\n```js\n    code();\n```\n"
+        );
+        assert_eq!(normalize_indentation_in("      deep\n", false, 4, false), "\t  deep\n");
+    }
+
+    #[test]
+    fn normalize_indentation_keeps_tabs_in_tab_significant_fences() {
+        let content = "```makefile\nbuild:\n\tcc main.c\n```\n\tafter\n";
+        assert_eq!(
+            normalize_indentation_in(content, true, 2, false),
+            "```makefile\nbuild:\n\tcc main.c\n```\n  after\n"
+        );
+    }
+
+    #[test]
+    fn normalize_indentation_preserve_code_blocks_leaves_every_fence_verbatim() {
+        let content = "\tbefore\n~~~python\n\tpass\n~~~\n";
+        assert_eq!(
+            normalize_indentation_in(content, true, 4, true),
+            "    before\n~~~python\n\tpass\n~~~\n"
+        );
+        assert_eq!(
+            normalize_indentation_in(content, true, 4, false),
+            "    before\n~~~python\n    pass\n~~~\n"
+        );
+    }
+
+    #[test]
+    fn normalize_indentation_closes_fences_only_with_a_matching_fence() {
+        // A shorter or different fence inside a four-backtick block doesn't close it
+        let content = "````go\n```\n\tx := 1\n~~~~\n````\n\ty\n";
+        assert_eq!(
+            normalize_indentation_in(content, true, 4, false),
+            "````go\n```\n\tx := 1\n~~~~\n````\n    y\n"
+        );
+    }
+
+    #[test]
+    fn parse_code_fence_reads_character_length_and_info() {
+        assert_eq!(parse_code_fence("````rust"), Some((CodeFence { ch: '`', len: 4 }, "rust")));
+        assert_eq!(parse_code_fence("   ~~~"), Some((CodeFence { ch: '~', len: 3 }, "")));
+        assert_eq!(parse_code_fence("``not a fence"), None);
+        assert_eq!(parse_code_fence("    ```"), None);
+    }
+
+    /// (level, text, line) for every heading, depth first
+    fn flatten(nodes: &[OutlineNode]) -> Vec<(u8, String, usize)> {
+        let mut flat = Vec::new();
+        for node in nodes {
+            flat.push((node.level, node.text.clone(), node.line));
+            flat.extend(flatten(&node.children));
+        }
+        flat
+    }
+
+    #[test]
+    fn parse_outline_reads_atx_headings() {
+        let outline = parse_outline("# Title\n\n## Section ##\n#hashtag\n    # indented code\n### Sub\n");
+        assert_eq!(
+            flatten(&outline),
+            vec![
+                (1, "Title".to_string(), 1),
+                (2, "Section".to_string(), 3),
+                (3, "Sub".to_string(), 6),
+            ]
+        );
+        // Nested by level
+        assert_eq!(outline.len(), 1);
+        assert_eq!(outline[0].children[0].children[0].text, "Sub");
+    }
+
+    #[test]
+    fn parse_outline_reads_setext_headings_after_paragraphs_only() {
+        let content = "Title\n=====\n\nSection\n-------\n\n- list item\n---\n\n1. step\n---\n\n> quote\n---\n";
+        assert_eq!(
+            flatten(&parse_outline(content)),
+            vec![(1, "Title".to_string(), 1), (2, "Section".to_string(), 4)]
+        );
+    }
+
+    #[test]
+    fn parse_outline_ignores_fenced_code_and_frontmatter() {
+        let content = "---\ntitle: Note\n---\n# Real\n```\n# not a heading\n```\n---\n~~~md\nText\n===\n~~~\n## After\n";
+        assert_eq!(
+            flatten(&parse_outline(content)),
+            vec![(1, "Real".to_string(), 4), (2, "After".to_string(), 13)]
+        );
+    }
+
+    fn find<'a>(nodes: &'a [TreeNode], name: &str) -> &'a TreeNode {
+        nodes.iter().find(|node| node.name == name).unwrap()
+    }
+
+    #[test]
+    fn build_status_tree_matches_statuses_in_a_nested_workspace() {
+        // The repository root is one level above the workspace
+        let repo = std::env::temp_dir().join(format!("my-kanban-tree-{}", Uuid::new_v4()));
+        let workspace = repo.join("workspace");
+        fs::create_dir_all(workspace.join("projects/drafts")).unwrap();
+        fs::write(workspace.join("index.md"), "").unwrap();
+        fs::write(workspace.join("staged.md"), "").unwrap();
+        fs::write(workspace.join("projects/plan.md"), "").unwrap();
+        fs::write(workspace.join("projects/drafts/idea.md"), "").unwrap();
+        fs::write(workspace.join("projects/done.md"), "").unwrap();
+
+        let output = concat!(
+            " M workspace/projects/plan.md\0",
+            "?? workspace/projects/drafts/idea.md\0",
+            "A  workspace/staged.md\0",
+            " M outside.md\0",
+        );
+        let statuses = porcelain_statuses(output, "workspace/");
+        assert_eq!(statuses.len(), 3);
+
+        let tree = build_status_tree(&workspace, &workspace, &statuses);
+        assert_eq!(find(&tree, "index.md").status, FileGitStatus::Clean);
+        assert_eq!(find(&tree, "staged.md").status, FileGitStatus::Staged);
+
+        let projects = find(&tree, "projects");
+        assert_eq!(projects.status, FileGitStatus::Modified);
+        assert_eq!(find(&projects.children, "plan.md").status, FileGitStatus::Modified);
+        assert_eq!(find(&projects.children, "plan.md").path, "projects/plan.md");
+        assert_eq!(find(&projects.children, "done.md").status, FileGitStatus::Clean);
+
+        let drafts = find(&projects.children, "drafts");
+        assert_eq!(drafts.status, FileGitStatus::Modified);
+        assert_eq!(find(&drafts.children, "idea.md").status, FileGitStatus::Untracked);
+
+        fs::remove_dir_all(&repo).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::notes::{collect_markdown_files, read_note_identity};
+
+/// Title terms count this many times more than body terms when ranking
+const TITLE_WEIGHT: f32 = 3.0;
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub path: String,
+    pub title: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexStats {
+    pub document_count: usize,
+    pub term_count: usize,
+}
+
+struct IndexedDoc {
+    title: String,
+    /// Distinct terms of this doc, so its postings can be removed on update
+    terms: Vec<String>,
+}
+
+#[derive(Default)]
+struct IndexData {
+    root: Option<PathBuf>,
+    /// term -> (path -> weighted term frequency); ordered so prefix lookups are a range scan
+    postings: BTreeMap<String, HashMap<String, f32>>,
+    docs: HashMap<String, IndexedDoc>,
+}
+
+impl IndexData {
+    fn remove_doc(&mut self, path: &str) {
+        if let Some(doc) = self.docs.remove(path) {
+            for term in doc.terms {
+                if let Some(entries) = self.postings.get_mut(&term) {
+                    entries.remove(path);
+                    if entries.is_empty() {
+                        self.postings.remove(&term);
+                    }
+                }
+            }
+        }
+    }
+
+    fn index_doc(&mut self, path: &Path) {
+        let path_str = path.to_string_lossy().to_string();
+        self.remove_doc(&path_str);
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(_) => return,
+        };
+
+        let identity = read_note_identity(&content);
+        let title = identity
+            .title
+            .or(identity.heading)
+            .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+            .unwrap_or_default();
+
+        let mut frequencies: HashMap<String, f32> = HashMap::new();
+        for term in tokenize(&content) {
+            *frequencies.entry(term).or_insert(0.0) += 1.0;
+        }
+        for term in tokenize(&title) {
+            *frequencies.entry(term).or_insert(0.0) += TITLE_WEIGHT;
+        }
+
+        for (term, frequency) in &frequencies {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(path_str.clone(), *frequency);
+        }
+
+        self.docs.insert(
+            path_str,
+            IndexedDoc {
+                title,
+                terms: frequencies.into_keys().collect(),
+            },
+        );
+    }
+}
+
+/// Split text into lowercase NFC word terms
+fn tokenize(text: &str) -> Vec<String> {
+    let normalized: String = text.nfc().collect::<String>().to_lowercase();
+    normalized
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// Inverted index over the workspace's notes, kept fresh by the workspace watcher
+pub struct SearchIndex {
+    data: Arc<Mutex<IndexData>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(IndexData::default())),
+        }
+    }
+
+    pub fn build(&self, workspace_path: &str) -> IndexStats {
+        let root = PathBuf::from(workspace_path);
+        let mut data = IndexData {
+            root: Some(root.clone()),
+            ..IndexData::default()
+        };
+
+        for path in collect_markdown_files(&root) {
+            data.index_doc(&path);
+        }
+
+        let stats = IndexStats {
+            document_count: data.docs.len(),
+            term_count: data.postings.len(),
+        };
+        *self.data.lock().unwrap() = data;
+        stats
+    }
+
+    /// Re-index (or drop, if deleted) the given paths. Paths outside the indexed
+    /// workspace are ignored, as is everything before the index has been built.
+    pub fn refresh_paths(&self, paths: &[PathBuf]) {
+        let mut data = self.data.lock().unwrap();
+        let root = match &data.root {
+            Some(root) => root.clone(),
+            None => return,
+        };
+
+        for path in paths {
+            if !path.starts_with(&root) {
+                continue;
+            }
+            if path.is_file() && path.extension().map(|ext| ext == "md").unwrap_or(false) {
+                data.index_doc(path);
+            } else {
+                data.remove_doc(&path.to_string_lossy());
+            }
+        }
+    }
+
+    /// Rank notes containing every query term. The last term is matched as a prefix
+    /// so results update as the user types.
+    pub fn query(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let data = self.data.lock().unwrap();
+        let doc_count = data.docs.len() as f32;
+        let mut scores: Option<HashMap<String, f32>> = None;
+
+        for (i, term) in terms.iter().enumerate() {
+            let is_prefix = i == terms.len() - 1;
+
+            // Per-term scores, summed over all expansions of a prefix term
+            let mut term_scores: HashMap<String, f32> = HashMap::new();
+            let matching = data
+                .postings
+                .range(term.clone()..)
+                .take_while(|(candidate, _)| {
+                    if is_prefix {
+                        candidate.starts_with(term.as_str())
+                    } else {
+                        *candidate == term
+                    }
+                });
+
+            for (_, entries) in matching {
+                let idf = (1.0 + doc_count / entries.len() as f32).ln();
+                for (path, frequency) in entries {
+                    *term_scores.entry(path.clone()).or_insert(0.0) += frequency * idf;
+                }
+            }
+
+            // Intersect with the docs that matched every previous term
+            scores = Some(match scores {
+                None => term_scores,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter_map(|(path, score)| term_scores.get(&path).map(|s| (path, score + s)))
+                    .collect(),
+            });
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(path, score)| SearchHit {
+                title: data.docs.get(&path).map(|doc| doc.title.clone()).unwrap_or_default(),
+                path,
+                score,
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        hits.truncate(limit);
+        hits
+    }
+}
+
+#[tauri::command]
+pub async fn build_search_index(
+    search_index: tauri::State<'_, SearchIndex>,
+    workspace_path: String,
+) -> Result<IndexStats, String> {
+    if !Path::new(&workspace_path).is_dir() {
+        return Err(format!("Workspace not found: {}", workspace_path));
+    }
+    Ok(search_index.build(&workspace_path))
+}
+
+#[tauri::command]
+pub async fn query_index(
+    search_index: tauri::State<'_, SearchIndex>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    Ok(search_index.query(&query, limit.unwrap_or(50)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed_workspace(notes: &[(&str, &str)]) -> (PathBuf, SearchIndex) {
+        let root = std::env::temp_dir().join(format!("my-kanban-search-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        for (name, content) in notes {
+            fs::write(root.join(name), content).unwrap();
+        }
+        let index = SearchIndex::new();
+        index.build(&root.to_string_lossy());
+        (root, index)
+    }
+
+    fn hit_names(hits: &[SearchHit]) -> Vec<String> {
+        hits.iter()
+            .map(|hit| Path::new(&hit.path).file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_lowercases_splits_and_normalizes() {
+        assert_eq!(tokenize("Hello, World! foo_bar 42"), vec!["hello", "world", "foo", "bar", "42"]);
+        // Decomposed "é" matches the precomposed spelling
+        assert_eq!(tokenize("Cafe\u{301}"), tokenize("Caf\u{e9}"));
+        assert!(tokenize("  --- ").is_empty());
+    }
+
+    #[test]
+    fn query_ranks_title_matches_above_body_matches() {
+        let (root, index) = indexed_workspace(&[
+            ("body.md", "# Notes\n\nThe roadmap is in progress.\n"),
+            ("title.md", "# Roadmap\n\nQuarterly goals.\n"),
+        ]);
+
+        let hits = index.query("roadmap", 10);
+        assert_eq!(hit_names(&hits), vec!["title.md", "body.md"]);
+        assert!(hits[0].score > hits[1].score);
+        assert_eq!(hits[0].title, "Roadmap");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn query_matches_last_term_as_prefix_and_requires_every_term() {
+        let (root, index) = indexed_workspace(&[
+            ("both.md", "kanban board planning\n"),
+            ("one.md", "kanban only\n"),
+            ("other.md", "planning only\n"),
+        ]);
+
+        let mut names = hit_names(&index.query("kanban", 10));
+        names.sort();
+        assert_eq!(names, vec!["both.md", "one.md"]);
+        // Only the last term is a prefix
+        assert_eq!(hit_names(&index.query("kanban plan", 10)), vec!["both.md"]);
+        assert!(index.query("kan planning", 10).is_empty());
+        assert!(index.query("", 10).is_empty());
+        assert_eq!(index.query("only", 1).len(), 1);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn refresh_paths_reindexes_and_drops_notes() {
+        let (root, index) = indexed_workspace(&[("page.md", "apple\n")]);
+        let page = root.join("page.md");
+
+        fs::write(&page, "banana\n").unwrap();
+        index.refresh_paths(&[page.clone()]);
+        assert!(index.query("apple", 10).is_empty());
+        assert_eq!(hit_names(&index.query("banana", 10)), vec!["page.md"]);
+
+        fs::remove_file(&page).unwrap();
+        index.refresh_paths(&[page]);
+        assert!(index.query("banana", 10).is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
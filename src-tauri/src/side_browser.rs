@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use tauri::{AppHandle, Emitter, Manager, Url, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::window_state::{load_config_json, save_config_json, MAIN_WINDOW_LABEL};
+
+const SIDE_BROWSER_LABEL: &str = "side-browser";
+/// Each additional open side browser is shifted by this much so none is fully hidden
+const STACK_OFFSET: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct WindowBounds {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DockPosition {
+    #[default]
+    Right,
+    Left,
+    Bottom,
+}
+
+const MIN_ZOOM: f64 = 0.5;
+const MAX_ZOOM: f64 = 3.0;
+
+const DEFAULT_SPLIT_RATIO: f64 = 0.5;
+const MIN_SPLIT_RATIO: f64 = 0.2;
+const MAX_SPLIT_RATIO: f64 = 0.8;
+
+/// Footprint of the side browser: `ratio` of the main window's width (or height, when
+/// docked to the bottom) on the dock side
+fn dock_bounds(main_bounds: &WindowBounds, position: DockPosition, ratio: f64) -> WindowBounds {
+    match position {
+        DockPosition::Right => {
+            let width = main_bounds.width * ratio;
+            WindowBounds {
+                x: main_bounds.x + main_bounds.width - width,
+                y: main_bounds.y,
+                width,
+                height: main_bounds.height,
+            }
+        }
+        DockPosition::Left => WindowBounds {
+            x: main_bounds.x,
+            y: main_bounds.y,
+            width: main_bounds.width * ratio,
+            height: main_bounds.height,
+        },
+        DockPosition::Bottom => {
+            let height = main_bounds.height * ratio;
+            WindowBounds {
+                x: main_bounds.x,
+                y: main_bounds.y + main_bounds.height - height,
+                width: main_bounds.width,
+                height,
+            }
+        }
+    }
+}
+
+/// Side browser preferences kept in the app config dir across restarts
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct SideBrowserSettings {
+    /// Last saved bounds per side browser window label
+    #[serde(default)]
+    bounds: HashMap<String, WindowBounds>,
+    /// Last zoom factor, applied to every side browser when it opens
+    #[serde(default)]
+    zoom: Option<f64>,
+}
+
+const SETTINGS_FILE: &str = "side-browser.json";
+
+/// Missing or unreadable settings fall back to defaults rather than failing the caller
+fn load_settings(app: &AppHandle) -> SideBrowserSettings {
+    load_config_json(app, SETTINGS_FILE).unwrap_or_default()
+}
+
+fn save_settings(app: &AppHandle, settings: &SideBrowserSettings) -> Result<(), String> {
+    save_config_json(app, SETTINGS_FILE, settings)
+}
+
+/// Payload of `side-browser-navigated`, sent to the main window
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SideBrowserNavigation {
+    /// Window label of the side browser that navigated
+    pub label: String,
+    pub url: String,
+    /// `None` until the page reports its title, which arrives as a follow-up event
+    pub title: Option<String>,
+}
+
+fn parse_url(url: &str) -> Result<Url, String> {
+    url.parse().map_err(|e| format!("Invalid URL: {}", e))
+}
+
+/// Window label for a side browser: `side-browser`, or `side-browser-<id>` for a named one
+fn side_browser_label(id: Option<&str>) -> Result<String, String> {
+    match id {
+        None => Ok(SIDE_BROWSER_LABEL.to_string()),
+        Some(id) => {
+            let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                return Err(format!("Invalid side browser id: {}", id));
+            }
+            Ok(format!("{}-{}", SIDE_BROWSER_LABEL, id))
+        }
+    }
+}
+
+fn is_side_browser_label(label: &str) -> bool {
+    label == SIDE_BROWSER_LABEL || label.starts_with(&format!("{}-", SIDE_BROWSER_LABEL))
+}
+
+fn side_browser_window(app: &AppHandle, id: Option<&str>) -> Result<WebviewWindow, String> {
+    let label = side_browser_label(id)?;
+    app.get_webview_window(&label)
+        .ok_or_else(|| "No side browser is open".to_string())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn open_side_browser(
+    app: AppHandle,
+    url: String,
+    main_bounds: WindowBounds,
+    position: Option<DockPosition>,
+    ratio: Option<f64>,
+    id: Option<String>,
+    user_agent: Option<String>,
+    initialization_script: Option<String>,
+) -> Result<(), String> {
+    let label = side_browser_label(id.as_deref())?;
+
+    // Close existing side browser with this label if it exists
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.close();
+    }
+
+    // Reuse the layout the user saved for this browser; otherwise dock to the
+    // requested side of the main window (right half by default)
+    let settings = load_settings(&app);
+    let bounds = match settings.bounds.get(&label) {
+        Some(saved) => *saved,
+        None => {
+            let ratio = ratio
+                .filter(|ratio| ratio.is_finite())
+                .unwrap_or(DEFAULT_SPLIT_RATIO)
+                .clamp(MIN_SPLIT_RATIO, MAX_SPLIT_RATIO);
+            let mut bounds = dock_bounds(&main_bounds, position.unwrap_or_default(), ratio);
+
+            // Stack on top of any other side browsers that are still open
+            let others = app
+                .webview_windows()
+                .keys()
+                .filter(|other| is_side_browser_label(other) && **other != label)
+                .count();
+            bounds.x += STACK_OFFSET * others as f64;
+            bounds.y += STACK_OFFSET * others as f64;
+            bounds
+        }
+    };
+
+    let nav_app = app.clone();
+    let nav_label = label.clone();
+    let title_app = app.clone();
+    let title_label = label.clone();
+
+    // Create a new webview window for the browser
+    let mut builder = WebviewWindowBuilder::new(
+        &app,
+        &label,
+        WebviewUrl::External(parse_url(&url)?)
+    );
+    // For sites that reject the default webview user agent
+    if let Some(user_agent) = user_agent.as_deref() {
+        builder = builder.user_agent(user_agent);
+    }
+    // Runs before each page's own scripts, e.g. to put an auth token in localStorage
+    if let Some(script) = initialization_script.as_deref() {
+        builder = builder.initialization_script(script);
+    }
+
+    let window = builder
+    .on_navigation(move |url| {
+        let navigation = SideBrowserNavigation {
+            label: nav_label.clone(),
+            url: url.to_string(),
+            title: None,
+        };
+        let _ = nav_app.emit_to(MAIN_WINDOW_LABEL, "side-browser-navigated", navigation);
+        true
+    })
+    .on_document_title_changed(move |window, title| {
+        let navigation = SideBrowserNavigation {
+            label: title_label.clone(),
+            url: window.url().map(|url| url.to_string()).unwrap_or_default(),
+            title: Some(title),
+        };
+        let _ = title_app.emit_to(MAIN_WINDOW_LABEL, "side-browser-navigated", navigation);
+    })
+    .title("Browser")
+    .position(bounds.x, bounds.y)
+    .inner_size(bounds.width, bounds.height)
+    .resizable(true)
+    .decorations(true)
+    .always_on_top(false)
+    .build()
+    .map_err(|e| e.to_string())?;
+
+    if let Some(zoom) = settings.zoom {
+        let _ = window.set_zoom(zoom);
+    }
+
+    Ok(())
+}
+
+/// Point the open side browser at a new URL in place, keeping the window (and its
+/// position) intact. Only opens a new window when none exists, which needs `main_bounds`.
+#[tauri::command]
+pub async fn navigate_side_browser(
+    app: AppHandle,
+    url: String,
+    main_bounds: Option<WindowBounds>,
+    id: Option<String>,
+) -> Result<(), String> {
+    let parsed = parse_url(&url)?;
+
+    match side_browser_window(&app, id.as_deref()) {
+        Ok(window) => window.navigate(parsed).map_err(|e| e.to_string()),
+        Err(_) => {
+            let main_bounds = main_bounds
+                .ok_or_else(|| "No side browser is open and no main window bounds were given".to_string())?;
+            open_side_browser(app, url, main_bounds, None, None, id, None, None).await
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn reload_side_browser(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let window = side_browser_window(&app, id.as_deref())?;
+    window.eval("location.reload()").map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn side_browser_back(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let window = side_browser_window(&app, id.as_deref())?;
+    window.eval("history.back()").map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn side_browser_forward(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let window = side_browser_window(&app, id.as_deref())?;
+    window.eval("history.forward()").map_err(|e| e.to_string())
+}
+
+/// Zoom the side browser's page; the factor is clamped and remembered for next time
+#[tauri::command]
+pub async fn set_side_browser_zoom(app: AppHandle, factor: f64, id: Option<String>) -> Result<f64, String> {
+    if !factor.is_finite() {
+        return Err(format!("Invalid zoom factor: {}", factor));
+    }
+    let factor = factor.clamp(MIN_ZOOM, MAX_ZOOM);
+
+    let window = side_browser_window(&app, id.as_deref())?;
+    window.set_zoom(factor).map_err(|e| e.to_string())?;
+
+    let mut settings = load_settings(&app);
+    settings.zoom = Some(factor);
+    save_settings(&app, &settings)?;
+    Ok(factor)
+}
+
+/// Remember a side browser's bounds for the next `open_side_browser`. Without explicit
+/// `bounds`, the open window's current position and size are saved.
+#[tauri::command]
+pub async fn save_side_browser_bounds(
+    app: AppHandle,
+    id: Option<String>,
+    bounds: Option<WindowBounds>,
+) -> Result<WindowBounds, String> {
+    let label = side_browser_label(id.as_deref())?;
+    let bounds = match bounds {
+        Some(bounds) => bounds,
+        None => {
+            let window = side_browser_window(&app, id.as_deref())?;
+            let scale = window.scale_factor().map_err(|e| e.to_string())?;
+            let position = window.outer_position().map_err(|e| e.to_string())?.to_logical::<f64>(scale);
+            let size = window.inner_size().map_err(|e| e.to_string())?.to_logical::<f64>(scale);
+            WindowBounds {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        }
+    };
+
+    let mut settings = load_settings(&app);
+    settings.bounds.insert(label, bounds);
+    save_settings(&app, &settings)?;
+    Ok(bounds)
+}
+
+/// Close every open side browser, named or not
+pub fn close_all_side_browsers(app: &AppHandle) {
+    for (label, window) in app.webview_windows() {
+        if is_side_browser_label(&label) {
+            let _ = window.close();
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn close_side_browser(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    let label = side_browser_label(id.as_deref())?;
+    if let Some(window) = app.get_webview_window(&label) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -1,24 +1,383 @@
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
-use std::collections::HashMap;
+use portable_pty::{native_pty_system, Child, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
 use std::io::{Read, Write};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
+use crate::window_state::{load_config_json, save_config_json};
+
+/// Milliseconds spent in each phase of `spawn_terminal`, emitted once the shell
+/// produces its first output as `terminal-spawn-timing`
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SpawnTiming {
+    pub session_id: String,
+    pub pty_open_ms: f64,
+    pub shell_detect_ms: f64,
+    pub spawn_ms: f64,
+    pub first_output_ms: Option<f64>,
+}
+
+fn elapsed_ms(since: Instant) -> f64 {
+    since.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Number of trailing bytes that start a multibyte UTF-8 character whose remaining bytes
+/// haven't been read yet; 0 when `bytes` ends on a character boundary
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    for i in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - i];
+        // Skip continuation bytes until the lead byte of the last character
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let char_len = match byte {
+            0xF0..=0xFF => 4,
+            0xE0..=0xEF => 3,
+            0xC0..=0xDF => 2,
+            _ => 1,
+        };
+        return if char_len > i { i } else { 0 };
+    }
+    0
+}
+
+/// `\r\n` (Windows) and lone `\r` (classic Mac) line endings become `\n`
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// OSC 7 escape announcing the shell's working directory: `ESC ] 7 ; file://host/path`
+#[cfg(not(windows))]
+const OSC7_PREFIX: &[u8] = b"\x1b]7;";
+
+/// Unterminated OSC 7 sequences longer than this are discarded
+#[cfg(not(windows))]
+const OSC7_MAX_LEN: usize = 4096;
+
+/// Extracts working-directory reports (OSC 7) from the output stream, carrying
+/// sequences that straddle read boundaries over to the next chunk
+#[cfg(not(windows))]
+#[derive(Default)]
+struct Osc7Parser {
+    pending: Vec<u8>,
+}
+
+#[cfg(not(windows))]
+impl Osc7Parser {
+    /// Feed a chunk of output, returning the most recent complete cwd report in it
+    fn feed(&mut self, data: &[u8]) -> Option<String> {
+        let mut buf = std::mem::take(&mut self.pending);
+        buf.extend_from_slice(data);
+
+        let mut latest = None;
+        let mut pos = 0;
+        while let Some(start) = find_subslice(&buf[pos..], OSC7_PREFIX).map(|i| pos + i) {
+            let body_start = start + OSC7_PREFIX.len();
+            // Terminated by BEL or ST (ESC \)
+            let terminator = buf[body_start..]
+                .iter()
+                .enumerate()
+                .find(|&(i, &b)| b == 0x07 || (b == 0x1b && buf.get(body_start + i + 1) == Some(&b'\\')))
+                .map(|(i, _)| body_start + i);
+
+            match terminator {
+                Some(end) => {
+                    if let Some(cwd) = parse_file_url(&String::from_utf8_lossy(&buf[body_start..end])) {
+                        latest = Some(cwd);
+                    }
+                    pos = end + 1;
+                }
+                None => {
+                    // Incomplete: keep it for the next chunk unless it's implausibly long
+                    if buf.len() - start <= OSC7_MAX_LEN {
+                        self.pending = buf[start..].to_vec();
+                    }
+                    return latest;
+                }
+            }
+        }
+
+        // Keep a trailing partial prefix (e.g. a lone ESC) in case the marker is split
+        let tail_start = buf.len().saturating_sub(OSC7_PREFIX.len() - 1).max(pos);
+        if let Some(i) = (tail_start..buf.len()).find(|&i| OSC7_PREFIX.starts_with(&buf[i..])) {
+            self.pending = buf[i..].to_vec();
+        }
+
+        latest
+    }
+}
+
+#[cfg(not(windows))]
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// `file://host/some%20dir` -> `/some dir`
+#[cfg(not(windows))]
+fn parse_file_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+
+    // Percent-decode
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    Some(String::from_utf8_lossy(&decoded).to_string())
+}
+
+/// Optional overrides for how `spawn_terminal` launches its process
+#[derive(Default)]
+pub struct SpawnOptions {
+    /// Program to run instead of the detected login shell (e.g. `fish`, `python3`)
+    pub shell: Option<String>,
+    pub args: Vec<String>,
+    /// Extra environment variables, layered over the inherited environment
+    pub env: HashMap<String, String>,
+    /// Initial PTY size, defaulting to 120x30
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+    /// Command typed into the shell right after it starts (e.g. `npm run dev`)
+    pub init_command: Option<String>,
+    /// Bytes of output retained for `get_terminal_scrollback`, default 200KB, at most 16MB
+    pub scrollback_cap: Option<usize>,
+    /// Stable id the UI gives this terminal (e.g. its tab id), under which its note is
+    /// persisted; without one the note lives only as long as the session
+    pub terminal_id: Option<String>,
+}
+
+/// Find the user's shell: $SHELL first, then common shells on PATH
+#[cfg(not(windows))]
+fn detect_shell() -> Result<String, String> {
+    std::env::var("SHELL")
+        .ok()
+        .and_then(|s| {
+            if !s.is_empty() && std::path::Path::new(&s).exists() {
+                Some(s)
+            } else {
+                None
+            }
+        })
+        .or_else(|| {
+            // Fallback order: zsh, bash, sh
+            for shell in &["zsh", "bash", "sh"] {
+                if which::which(shell).is_ok() {
+                    return Some(shell.to_string());
+                }
+            }
+            None
+        })
+        .ok_or_else(|| "No suitable shell found (tried: $SHELL, zsh, bash, sh)".to_string())
+}
+
+/// Find the user's shell on Windows: %COMSPEC% first, then cmd, PowerShell, pwsh
+#[cfg(windows)]
+fn detect_shell() -> Result<String, String> {
+    std::env::var("COMSPEC")
+        .ok()
+        .and_then(|s| {
+            if !s.is_empty() && std::path::Path::new(&s).exists() {
+                Some(s)
+            } else {
+                None
+            }
+        })
+        .or_else(|| {
+            for shell in &["cmd.exe", "powershell.exe", "pwsh.exe"] {
+                if let Ok(path) = which::which(shell) {
+                    return Some(path.to_string_lossy().to_string());
+                }
+            }
+            None
+        })
+        .ok_or_else(|| {
+            "No suitable shell found (tried: %COMSPEC%, cmd.exe, powershell.exe, pwsh.exe)".to_string()
+        })
+}
+
+/// Resolve an explicitly requested shell, either a path or a program on PATH
+fn resolve_shell(requested: &str) -> Result<String, String> {
+    if std::path::Path::new(requested).is_file() {
+        return Ok(requested.to_string());
+    }
+    which::which(requested)
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|_| format!("Shell not found on PATH: {}", requested))
+}
+
+/// Longest time output is held back to be coalesced into one event (~one frame)
+const EMIT_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Output batches are emitted early once they reach this size
+const EMIT_BATCH_BYTES: usize = 32 * 1024;
+
+/// Terminal notes by UI terminal id, kept in the app config dir so a terminal
+/// re-spawned under the same id after a restart gets its note back
+const NOTES_FILE: &str = "terminal-notes.json";
+
+fn load_notes(app_handle: &AppHandle) -> HashMap<String, String> {
+    load_config_json(app_handle, NOTES_FILE).unwrap_or_default()
+}
+
+/// Default number of output bytes retained per session for scrollback
+const DEFAULT_SCROLLBACK_CAP: usize = 200 * 1024;
+
+/// Largest scrollback a caller may request, so one session can't hold unbounded memory
+const MAX_SCROLLBACK_CAP: usize = 16 * 1024 * 1024;
+
+/// Lines of preceding output sent along with a pattern match
+const PATTERN_CONTEXT_LINES: usize = 2;
+
+/// Partial lines longer than this are scanned as-is rather than buffered further
+const PATTERN_LINE_CAP: usize = 16 * 1024;
+
+/// A regex subscription on a session's output stream
+struct PatternWatch {
+    id: String,
+    regex: Regex,
+    event_name: String,
+    /// Output after the last newline, waiting for the rest of its line
+    partial_line: String,
+    /// Most recent complete lines, for match context
+    context: VecDeque<String>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct PatternMatch {
+    pub session_id: String,
+    pub watch_id: String,
+    pub line: String,
+    pub context: Vec<String>,
+}
+
+/// Run each complete line of `output` through the session's pattern watches
+fn scan_pattern_watches(app: &AppHandle, session_id: &str, watches: &mut [PatternWatch], output: &str) {
+    for watch in watches.iter_mut() {
+        watch.partial_line.push_str(output);
+
+        let mut lines: Vec<String> = Vec::new();
+        while let Some(newline) = watch.partial_line.find('\n') {
+            let line: String = watch.partial_line.drain(..=newline).collect();
+            lines.push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+        if watch.partial_line.len() > PATTERN_LINE_CAP {
+            lines.push(std::mem::take(&mut watch.partial_line));
+        }
+
+        for line in lines {
+            if watch.regex.is_match(&line) {
+                let _ = app.emit(
+                    &watch.event_name,
+                    PatternMatch {
+                        session_id: session_id.to_string(),
+                        watch_id: watch.id.clone(),
+                        line: line.clone(),
+                        context: watch.context.iter().cloned().collect(),
+                    },
+                );
+            }
+            watch.context.push_back(line);
+            if watch.context.len() > PATTERN_CONTEXT_LINES {
+                watch.context.pop_front();
+            }
+        }
+    }
+}
+
+/// Replays wait at most this long between inputs, so idle stretches don't stall a demo
+const REPLAY_MAX_DELAY: Duration = Duration::from_secs(2);
+
+/// One chunk of input written to a session, relative to when recording started
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct RecordedInput {
+    pub offset_ms: u64,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct TerminalTranscript {
+    pub inputs: Vec<RecordedInput>,
+}
+
+struct Recording {
+    started: Instant,
+    inputs: Vec<RecordedInput>,
+}
+
 #[derive(Clone)]
 pub struct TerminalSession {
     #[allow(dead_code)]
     writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    /// Kept so the PTY can be resized after spawn
+    master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+    /// The shell process. Once the PTY reaches EOF the reader thread holds this lock
+    /// while it waits for the exit status, so killing goes through `killer` instead.
+    child: Arc<Mutex<Box<dyn Child + Send + Sync>>>,
+    /// Kills the shell when the session is closed, without needing the `child` lock
+    killer: Arc<Mutex<Box<dyn ChildKiller + Send + Sync>>>,
+    /// The shell's process id, captured at spawn
+    #[cfg_attr(windows, allow(dead_code))]
+    pid: Option<u32>,
+    /// Most recent raw output, oldest bytes dropped beyond the session's cap
+    scrollback: Arc<Mutex<VecDeque<u8>>>,
+    /// Directory the shell was spawned in
+    working_dir: String,
+    /// Latest directory reported by the shell via OSC 7
+    cwd: Arc<Mutex<Option<String>>>,
+    /// Free-form user description of what the terminal is for
+    note: Arc<Mutex<Option<String>>>,
+    /// The UI's id for this terminal, keying its persisted note
+    terminal_id: Option<String>,
+    pattern_watches: Arc<Mutex<Vec<PatternWatch>>>,
+    /// Input captured while a recording is running
+    recording: Arc<Mutex<Option<Recording>>>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TerminalInfo {
+    pub session_id: String,
+    pub working_dir: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TerminalMemoryUsage {
+    pub session_id: String,
+    pub scrollback_bytes: usize,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct TerminalMemoryReport {
+    pub sessions: Vec<TerminalMemoryUsage>,
+    pub total_bytes: usize,
 }
 
 pub struct TerminalManager {
     sessions: Arc<Mutex<HashMap<String, TerminalSession>>>,
+    /// Exit codes of shells that have exited, held until retrieved
+    exited: Arc<Mutex<HashMap<String, i32>>>,
 }
 
 impl TerminalManager {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
+            exited: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -26,60 +385,100 @@ impl TerminalManager {
         &self,
         app_handle: AppHandle,
         working_dir: String,
+        options: SpawnOptions,
     ) -> Result<String, String> {
         let session_id = Uuid::new_v4().to_string();
+        let spawn_started = Instant::now();
 
         // Create PTY system
         let pty_system = native_pty_system();
 
         // Create PTY with initial size
+        let cols = options.cols.unwrap_or(120);
+        let rows = options.rows.unwrap_or(30);
+        if cols == 0 || rows == 0 {
+            return Err(format!("Invalid terminal size: {}x{}", cols, rows));
+        }
+
         let pair = pty_system
             .openpty(PtySize {
-                rows: 30,
-                cols: 120,
+                rows,
+                cols,
                 pixel_width: 0,
                 pixel_height: 0,
             })
             .map_err(|e| format!("Failed to open PTY: {}", e))?;
+        let pty_open_ms = elapsed_ms(spawn_started);
 
         // Get reader and writer
         let mut reader = pair.master.try_clone_reader().map_err(|e| e.to_string())?;
         let writer = pair.master.take_writer().map_err(|e| e.to_string())?;
 
-        // Detect shell: Try $SHELL env var first, then fallback to common shells
-        let shell = std::env::var("SHELL")
-            .ok()
-            .and_then(|s| {
-                if !s.is_empty() && std::path::Path::new(&s).exists() {
-                    Some(s)
-                } else {
-                    None
-                }
-            })
-            .or_else(|| {
-                // Fallback order: zsh, bash, sh
-                for shell in &["zsh", "bash", "sh"] {
-                    if which::which(shell).is_ok() {
-                        return Some(shell.to_string());
-                    }
-                }
-                None
-            })
-            .ok_or_else(|| "No suitable shell found (tried: $SHELL, zsh, bash, sh)".to_string())?;
+        // Use the requested shell if given, otherwise detect the user's shell
+        let shell_detect_started = Instant::now();
+        let shell = match &options.shell {
+            Some(requested) => resolve_shell(requested)?,
+            None => detect_shell()?,
+        };
+        let shell_detect_ms = elapsed_ms(shell_detect_started);
 
-        // Build command - spawn detected shell
+        // Build command - spawn the shell with any extra arguments
         let mut cmd = CommandBuilder::new(&shell);
+        cmd.args(&options.args);
         cmd.cwd(&working_dir);
 
+        // Advertise 256-color support so color apps render correctly; caller env wins
+        #[cfg(not(windows))]
+        cmd.env("TERM", "xterm-256color");
+        for (key, value) in &options.env {
+            cmd.env(key, value);
+        }
+
         // Spawn child process
-        let _child = pair
+        let spawn_command_started = Instant::now();
+        let child = pair
             .slave
             .spawn_command(cmd)
             .map_err(|e| format!("Failed to spawn shell ({}): {}", shell, e))?;
+        let spawn_ms = elapsed_ms(spawn_command_started);
+
+        let mut timing = SpawnTiming {
+            session_id: session_id.clone(),
+            pty_open_ms,
+            shell_detect_ms,
+            spawn_ms,
+            first_output_ms: None,
+        };
 
         // Store session
+        let scrollback = Arc::new(Mutex::new(VecDeque::new()));
+        let scrollback_cap = options
+            .scrollback_cap
+            .unwrap_or(DEFAULT_SCROLLBACK_CAP)
+            .min(MAX_SCROLLBACK_CAP);
+        let pattern_watches = Arc::new(Mutex::new(Vec::new()));
+        let killer = Arc::new(Mutex::new(child.clone_killer()));
+        let pid = child.process_id();
+        let child = Arc::new(Mutex::new(child));
+        let cwd = Arc::new(Mutex::new(None));
         let session = TerminalSession {
             writer: Arc::new(Mutex::new(writer)),
+            master: Arc::new(Mutex::new(pair.master)),
+            child: child.clone(),
+            killer,
+            pid,
+            scrollback: scrollback.clone(),
+            working_dir: working_dir.clone(),
+            cwd: cwd.clone(),
+            note: Arc::new(Mutex::new(
+                options
+                    .terminal_id
+                    .as_ref()
+                    .and_then(|terminal_id| load_notes(&app_handle).remove(terminal_id)),
+            )),
+            terminal_id: options.terminal_id,
+            pattern_watches: pattern_watches.clone(),
+            recording: Arc::new(Mutex::new(None)),
         };
 
         self.sessions
@@ -87,58 +486,564 @@ impl TerminalManager {
             .unwrap()
             .insert(session_id.clone(), session);
 
-        // Spawn thread to read output
-        let output_session_id = session_id.clone();
+        // Reader thread: pull raw output off the PTY and hand it to the emitter
+        let (output_tx, output_rx) = mpsc::channel::<Vec<u8>>();
+        let exit_session_id = session_id.clone();
+        let exited = self.exited.clone();
+        let sessions = self.sessions.clone();
         let app_handle_clone = app_handle.clone();
         std::thread::spawn(move || {
             let mut buf = [0u8; 8192];
+            let mut timing_reported = false;
+            #[cfg(not(windows))]
+            let mut osc7 = Osc7Parser::default();
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) => break, // EOF
                     Ok(n) => {
+                        // Report spawn timing once the shell has produced its first output
+                        if !timing_reported {
+                            timing.first_output_ms = Some(elapsed_ms(spawn_started));
+                            let _ = app_handle_clone.emit("terminal-spawn-timing", timing.clone());
+                            timing_reported = true;
+                        }
                         let data = &buf[..n];
-                        // Try to convert to UTF-8, replacing invalid sequences
-                        let output = String::from_utf8_lossy(data).to_string();
-                        let event_name = format!("terminal-output-{}", output_session_id);
-                        let _ = app_handle_clone.emit(&event_name, output);
+
+                        // Retain output for scrollback, dropping the oldest bytes past the cap
+                        {
+                            let mut scrollback = scrollback.lock().unwrap();
+                            scrollback.extend(data);
+                            let overflow = scrollback.len().saturating_sub(scrollback_cap);
+                            scrollback.drain(..overflow);
+                        }
+
+                        // Track the shell's working directory
+                        #[cfg(not(windows))]
+                        if let Some(dir) = osc7.feed(data) {
+                            *cwd.lock().unwrap() = Some(dir);
+                        }
+
+                        if output_tx.send(data.to_vec()).is_err() {
+                            break;
+                        }
                     }
                     Err(_) => break,
                 }
             }
+            // Dropping output_tx lets the emitter flush what's left and exit
+            drop(output_tx);
+
+            // EOF means the shell closed the PTY; block until it's reaped so the exit code
+            // is never lost. close_terminal kills through the session's killer meanwhile.
+            let status = child.lock().unwrap().wait();
+            match status {
+                Ok(status) => {
+                    let code = status.exit_code() as i32;
+                    {
+                        // Closed sessions have nobody left to collect their exit code
+                        let sessions = sessions.lock().unwrap();
+                        if sessions.contains_key(&exit_session_id) {
+                            exited.lock().unwrap().insert(exit_session_id.clone(), code);
+                        }
+                    }
+                    let _ = app_handle_clone.emit(&format!("terminal-exit-{}", exit_session_id), code);
+                }
+                Err(e) => eprintln!("[Terminal] Failed to wait for session {}: {}", exit_session_id, e),
+            }
+        });
+
+        // Emitter thread: coalesce reads so chatty programs don't flood the event bridge.
+        // A batch is flushed EMIT_INTERVAL after its first chunk or once it reaches
+        // EMIT_BATCH_BYTES, whichever comes first; ordering is preserved.
+        let output_session_id = session_id.clone();
+        let app_handle_clone = app_handle.clone();
+        std::thread::spawn(move || {
+            let event_name = format!("terminal-output-{}", output_session_id);
+            // Start of a multibyte character split across reads, held for the next batch
+            let mut pending: Vec<u8> = Vec::new();
+
+            // Blocks until the first chunk of the next batch (or EOF)
+            while let Ok(first) = output_rx.recv() {
+                let mut batch = std::mem::take(&mut pending);
+                batch.extend_from_slice(&first);
+                let deadline = Instant::now() + EMIT_INTERVAL;
+
+                while batch.len() < EMIT_BATCH_BYTES {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    match output_rx.recv_timeout(remaining) {
+                        Ok(chunk) => batch.extend_from_slice(&chunk),
+                        Err(_) => break, // Interval elapsed, or reader finished
+                    }
+                }
+
+                // Only decode up to the last complete character; genuinely invalid
+                // sequences are still replaced
+                let tail = incomplete_utf8_tail(&batch);
+                pending = batch.split_off(batch.len() - tail);
+                if batch.is_empty() {
+                    continue;
+                }
+                let output = String::from_utf8_lossy(&batch).to_string();
+
+                {
+                    let mut watches = pattern_watches.lock().unwrap();
+                    if !watches.is_empty() {
+                        scan_pattern_watches(&app_handle_clone, &output_session_id, &mut watches, &output);
+                    }
+                }
+
+                let _ = app_handle_clone.emit(&event_name, output);
+            }
+
+            // The shell exited mid-character; flush what's left as replacement characters
+            if !pending.is_empty() {
+                let _ = app_handle_clone.emit(&event_name, String::from_utf8_lossy(&pending).to_string());
+            }
         });
 
+        // Run the initial command now that the reader is capturing its output
+        if let Some(init_command) = options.init_command {
+            self.write_terminal(session_id.clone(), format!("{}\n", init_command), true)?;
+        }
+
         Ok(session_id)
     }
 
-    pub fn write_terminal(&self, session_id: String, data: String) -> Result<(), String> {
+    /// Write text input. With `normalize_newlines`, line endings are unified (see
+    /// `normalize_line_endings`) so pasted text doesn't run lines twice or echo stray `^M`s.
+    pub fn write_terminal(&self, session_id: String, data: String, normalize_newlines: bool) -> Result<(), String> {
+        if normalize_newlines {
+            self.write_terminal_bytes(session_id, normalize_line_endings(&data).as_bytes())
+        } else {
+            self.write_terminal_bytes(session_id, data.as_bytes())
+        }
+    }
+
+    pub fn write_terminal_bytes(&self, session_id: String, data: &[u8]) -> Result<(), String> {
         let sessions = self.sessions.lock().unwrap();
         let session = sessions
             .get(&session_id)
             .ok_or_else(|| "Session not found".to_string())?;
 
+        if let Some(recording) = session.recording.lock().unwrap().as_mut() {
+            recording.inputs.push(RecordedInput {
+                offset_ms: recording.started.elapsed().as_millis() as u64,
+                data: data.to_vec(),
+            });
+        }
+
         let mut writer = session.writer.lock().unwrap();
         writer
-            .write_all(data.as_bytes())
+            .write_all(data)
             .map_err(|e| e.to_string())?;
         writer.flush().map_err(|e| e.to_string())?;
 
         Ok(())
     }
 
+    /// Start capturing everything written to the session; restarting discards the old capture
+    pub fn start_terminal_recording(&self, session_id: String) -> Result<(), String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        *session.recording.lock().unwrap() = Some(Recording {
+            started: Instant::now(),
+            inputs: Vec::new(),
+        });
+        Ok(())
+    }
+
+    pub fn stop_terminal_recording(&self, session_id: String) -> Result<TerminalTranscript, String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let recording = session
+            .recording
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| "Session is not being recorded".to_string())?;
+        Ok(TerminalTranscript {
+            inputs: recording.inputs,
+        })
+    }
+
+    /// Write a transcript's inputs back to a session with their original spacing (each
+    /// delay capped at `REPLAY_MAX_DELAY`). Runs in the background; returns immediately.
+    pub fn replay_terminal(&self, session_id: String, transcript: TerminalTranscript) -> Result<(), String> {
+        let writer = {
+            let sessions = self.sessions.lock().unwrap();
+            let session = sessions
+                .get(&session_id)
+                .ok_or_else(|| "Session not found".to_string())?;
+            session.writer.clone()
+        };
+
+        std::thread::spawn(move || {
+            let mut previous_offset = 0;
+            for input in transcript.inputs {
+                let delay = Duration::from_millis(input.offset_ms.saturating_sub(previous_offset));
+                std::thread::sleep(delay.min(REPLAY_MAX_DELAY));
+                previous_offset = input.offset_ms;
+
+                let mut writer = writer.lock().unwrap();
+                if writer.write_all(&input.data).and_then(|_| writer.flush()).is_err() {
+                    eprintln!("[Terminal] Replay to session {} stopped: write failed", session_id);
+                    break;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Write the same input to several sessions; returns the ids that couldn't be written
+    pub fn broadcast_terminal_input(&self, session_ids: Vec<String>, data: String) -> Vec<String> {
+        session_ids
+            .into_iter()
+            .filter(|session_id| match self.write_terminal_bytes(session_id.clone(), data.as_bytes()) {
+                Ok(()) => false,
+                Err(e) => {
+                    eprintln!("[Terminal] Broadcast to session {} failed: {}", session_id, e);
+                    true
+                }
+            })
+            .collect()
+    }
+
+    /// Shell's current directory as last reported via OSC 7, else its spawn directory
+    pub fn get_terminal_cwd(&self, session_id: String) -> Result<String, String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let cwd = session.cwd.lock().unwrap().clone();
+        Ok(cwd.unwrap_or_else(|| session.working_dir.clone()))
+    }
+
+    /// Buffered output of a session, for repainting history after the UI reconnects
+    pub fn get_terminal_scrollback(&self, session_id: String) -> Result<String, String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let scrollback = session.scrollback.lock().unwrap();
+        let (front, back) = scrollback.as_slices();
+        let mut bytes = Vec::with_capacity(scrollback.len());
+        bytes.extend_from_slice(front);
+        bytes.extend_from_slice(back);
+
+        Ok(String::from_utf8_lossy(&bytes).to_string())
+    }
+
+    /// Clear the screen and scrollback (xterm `ED 2`, `ED 3`, cursor home) and drop the
+    /// server-side scrollback so a re-attaching UI doesn't repaint the old output. The
+    /// escape goes to the UI as output; written to the PTY the shell would read it as input.
+    pub fn clear_terminal(&self, app_handle: AppHandle, session_id: String) -> Result<(), String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+        session.scrollback.lock().unwrap().clear();
+
+        app_handle
+            .emit(&format!("terminal-output-{}", session_id), "\x1b[2J\x1b[3J\x1b[H")
+            .map_err(|e| e.to_string())
+    }
+
     pub fn resize_terminal(
         &self,
-        _session_id: String,
-        _cols: u16,
-        _rows: u16,
+        session_id: String,
+        cols: u16,
+        rows: u16,
     ) -> Result<(), String> {
-        // PTY resizing would require keeping reference to the master PTY
-        // For now, we'll skip this as it's not critical
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let master = session.master.lock().unwrap();
+        master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to resize PTY: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Send `SIGINT`, `SIGTERM` or `SIGKILL` to the terminal's foreground process group,
+    /// so a stuck program is stopped even if it ignores input like `\x03`. Falls back to
+    /// the shell's own process group when the foreground group can't be determined.
+    #[cfg(unix)]
+    pub fn signal_terminal(&self, session_id: String, signal: String) -> Result<(), String> {
+        let signo = match signal.as_str() {
+            "SIGINT" => libc::SIGINT,
+            "SIGTERM" => libc::SIGTERM,
+            "SIGKILL" => libc::SIGKILL,
+            _ => return Err(format!("Unsupported signal: {}", signal)),
+        };
+
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let pgid = session
+            .master
+            .lock()
+            .unwrap()
+            .process_group_leader()
+            .or_else(|| session.pid.map(|pid| pid as libc::pid_t))
+            .ok_or_else(|| "Terminal process is not running".to_string())?;
+
+        // SAFETY: killpg has no memory-safety preconditions; failure is reported via errno
+        if unsafe { libc::killpg(pgid, signo) } != 0 {
+            return Err(format!(
+                "Failed to send {} to process group {}: {}",
+                signal,
+                pgid,
+                std::io::Error::last_os_error()
+            ));
+        }
         Ok(())
     }
 
+    /// Windows has no signals to deliver; only `SIGKILL` is supported, by killing the shell
+    #[cfg(windows)]
+    pub fn signal_terminal(&self, session_id: String, signal: String) -> Result<(), String> {
+        if signal != "SIGKILL" {
+            return Err(format!("Unsupported signal on Windows: {}", signal));
+        }
+
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+        let mut killer = session.killer.lock().unwrap();
+        killer.kill().map_err(|e| format!("Failed to kill terminal process: {}", e))
+    }
+
+    /// Whether the session's shell is still running; `false` for unknown sessions
+    pub fn is_terminal_alive(&self, session_id: String) -> bool {
+        let sessions = self.sessions.lock().unwrap();
+        match sessions.get(&session_id) {
+            // A held lock means the reader thread is still waiting for the shell to exit
+            Some(session) => match session.child.try_lock() {
+                Ok(mut child) => matches!(child.try_wait(), Ok(None)),
+                Err(_) => true,
+            },
+            None => false,
+        }
+    }
+
+    /// Resize every session's PTY to the same size; returns the ids that failed
+    pub fn resize_all_terminals(&self, cols: u16, rows: u16) -> Result<Vec<String>, String> {
+        if cols == 0 || rows == 0 {
+            return Err(format!("Invalid terminal size: {}x{}", cols, rows));
+        }
+
+        let session_ids: Vec<String> = self.sessions.lock().unwrap().keys().cloned().collect();
+        Ok(session_ids
+            .into_iter()
+            .filter(|session_id| match self.resize_terminal(session_id.clone(), cols, rows) {
+                Ok(()) => false,
+                Err(e) => {
+                    eprintln!("[Terminal] Resize of session {} failed: {}", session_id, e);
+                    true
+                }
+            })
+            .collect())
+    }
+
+    /// Exit code of a session's shell once it has exited. Retrieval consumes the entry.
+    pub fn get_terminal_exit_code(&self, session_id: String) -> Option<i32> {
+        self.exited.lock().unwrap().remove(&session_id)
+    }
+
+    pub fn list_terminals(&self) -> Vec<TerminalInfo> {
+        let sessions = self.sessions.lock().unwrap();
+        let mut terminals: Vec<TerminalInfo> = sessions
+            .iter()
+            .map(|(session_id, session)| TerminalInfo {
+                session_id: session_id.clone(),
+                working_dir: session.working_dir.clone(),
+                note: session.note.lock().unwrap().clone(),
+            })
+            .collect();
+        terminals.sort_by(|a, b| a.session_id.cmp(&b.session_id));
+        terminals
+    }
+
+    pub fn set_terminal_note(&self, app_handle: &AppHandle, session_id: String, text: String) -> Result<(), String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        // An empty note clears it
+        let text = text.trim();
+        let note = if text.is_empty() { None } else { Some(text.to_string()) };
+        *session.note.lock().unwrap() = note.clone();
+
+        // Sessions the UI gave no stable id keep their note in memory only
+        let terminal_id = match &session.terminal_id {
+            Some(terminal_id) => terminal_id,
+            None => return Ok(()),
+        };
+        let mut notes = load_notes(app_handle);
+        match note {
+            Some(note) => notes.insert(terminal_id.clone(), note),
+            None => notes.remove(terminal_id),
+        };
+        save_config_json(app_handle, NOTES_FILE, &notes)
+    }
+
+    pub fn get_terminal_note(&self, session_id: String) -> Result<Option<String>, String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let note = session.note.lock().unwrap().clone();
+        Ok(note)
+    }
+
+    pub fn watch_terminal_pattern(
+        &self,
+        session_id: String,
+        pattern: String,
+        event_name: String,
+    ) -> Result<String, String> {
+        let regex = Regex::new(&pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+
+        // Tauri only accepts alphanumerics and - / : _ in event names
+        let valid_name = !event_name.is_empty()
+            && event_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'));
+        if !valid_name {
+            return Err(format!("Invalid event name: {}", event_name));
+        }
+
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let watch_id = Uuid::new_v4().to_string();
+        session.pattern_watches.lock().unwrap().push(PatternWatch {
+            id: watch_id.clone(),
+            regex,
+            event_name,
+            partial_line: String::new(),
+            context: VecDeque::new(),
+        });
+
+        Ok(watch_id)
+    }
+
+    pub fn unwatch_terminal_pattern(&self, session_id: String, watch_id: String) -> Result<(), String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions
+            .get(&session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        let mut watches = session.pattern_watches.lock().unwrap();
+        let before = watches.len();
+        watches.retain(|watch| watch.id != watch_id);
+        if watches.len() == before {
+            return Err("Pattern watch not found".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn memory_report(&self) -> TerminalMemoryReport {
+        let sessions = self.sessions.lock().unwrap();
+        let mut usages: Vec<TerminalMemoryUsage> = sessions
+            .iter()
+            .map(|(session_id, session)| TerminalMemoryUsage {
+                session_id: session_id.clone(),
+                scrollback_bytes: session.scrollback.lock().unwrap().len(),
+            })
+            .collect();
+        usages.sort_by(|a, b| b.scrollback_bytes.cmp(&a.scrollback_bytes));
+
+        let total_bytes = usages.iter().map(|usage| usage.scrollback_bytes).sum();
+        TerminalMemoryReport {
+            sessions: usages,
+            total_bytes,
+        }
+    }
+
     pub fn close_terminal(&self, session_id: String) -> Result<(), String> {
         let mut sessions = self.sessions.lock().unwrap();
-        sessions.remove(&session_id);
+        if let Some(session) = sessions.remove(&session_id) {
+            // Kill the shell so it doesn't outlive its tab; the reader thread then
+            // sees EOF and reaps it
+            if let Err(e) = session.killer.lock().unwrap().kill() {
+                eprintln!("[Terminal] Failed to kill shell for session {}: {}", session_id, e);
+            }
+        }
+        // Nothing will ask for a closed session's exit code
+        self.exited.lock().unwrap().remove(&session_id);
         Ok(())
     }
+
+    /// Kill every session's shell, e.g. when the app is shutting down
+    pub fn close_all(&self) {
+        let session_ids: Vec<String> = self.sessions.lock().unwrap().keys().cloned().collect();
+        for session_id in session_ids {
+            let _ = self.close_terminal(session_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incomplete_utf8_tail_holds_back_split_characters() {
+        for ch in ['é', '€', '😀'] {
+            let mut encoded = [0u8; 4];
+            let bytes = ch.encode_utf8(&mut encoded).as_bytes();
+
+            for split in 1..bytes.len() {
+                let mut first = b"ab".to_vec();
+                first.extend_from_slice(&bytes[..split]);
+                assert_eq!(incomplete_utf8_tail(&first), split, "{:?} split after {} bytes", ch, split);
+
+                // Carrying the tail into the next read reassembles the character
+                let tail = first.split_off(first.len() - split);
+                let mut second = tail;
+                second.extend_from_slice(&bytes[split..]);
+                assert_eq!(String::from_utf8(first).unwrap(), "ab");
+                assert_eq!(String::from_utf8(second).unwrap(), ch.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn incomplete_utf8_tail_is_empty_after_a_complete_character() {
+        for text in ["ab", "abé", "ab€", "ab😀"] {
+            assert_eq!(incomplete_utf8_tail(text.as_bytes()), 0, "{:?}", text);
+        }
+        assert_eq!(incomplete_utf8_tail(b""), 0);
+    }
+
+    #[test]
+    fn normalize_line_endings_unifies_mixed_endings() {
+        let pasted = "one\r\ntwo\rthree\nfour\r\n\r\nfive\r";
+        assert_eq!(normalize_line_endings(pasted), "one\ntwo\nthree\nfour\n\nfive\n");
+        assert_eq!(normalize_line_endings("no newline"), "no newline");
+    }
 }
@@ -0,0 +1,191 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Manager, Monitor, Window};
+
+use crate::side_browser::WindowBounds;
+
+/// Label of the app's main window (the default when tauri.conf.json sets none)
+pub(crate) const MAIN_WINDOW_LABEL: &str = "main";
+const WINDOW_STATE_FILE: &str = "window-state.json";
+
+fn config_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))?;
+    Ok(dir.join(file_name))
+}
+
+/// Read a JSON file from the app config dir; `None` when missing or unreadable
+pub(crate) fn load_config_json<T: DeserializeOwned>(app: &AppHandle, file_name: &str) -> Option<T> {
+    let path = config_path(app, file_name).ok()?;
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub(crate) fn save_config_json<T: Serialize>(app: &AppHandle, file_name: &str, value: &T) -> Result<(), String> {
+    let path = config_path(app, file_name)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    crate::notes::write_atomic(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to save {}: {}", file_name, e))
+}
+
+fn monitor_bounds(monitor: &Monitor) -> WindowBounds {
+    let scale = monitor.scale_factor();
+    let position = monitor.position().to_logical::<f64>(scale);
+    let size = monitor.size().to_logical::<f64>(scale);
+    WindowBounds {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    }
+}
+
+fn overlap_area(a: &WindowBounds, b: &WindowBounds) -> f64 {
+    let width = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+    let height = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+    width.max(0.0) * height.max(0.0)
+}
+
+/// Fit saved bounds onto the monitor they overlap most, or the first monitor (the primary)
+/// when they're on none of them, e.g. after a display was unplugged. The window is shrunk
+/// if needed and moved so it lies fully on that monitor.
+fn clamp_to_monitors(bounds: WindowBounds, monitors: &[WindowBounds]) -> WindowBounds {
+    let first = match monitors.first() {
+        Some(first) => first,
+        None => return bounds,
+    };
+    let monitor = monitors
+        .iter()
+        .filter(|monitor| overlap_area(&bounds, monitor) > 0.0)
+        .max_by(|a, b| overlap_area(&bounds, a).total_cmp(&overlap_area(&bounds, b)))
+        .unwrap_or(first);
+
+    let width = bounds.width.min(monitor.width);
+    let height = bounds.height.min(monitor.height);
+    WindowBounds {
+        x: bounds.x.clamp(monitor.x, monitor.x + monitor.width - width),
+        y: bounds.y.clamp(monitor.y, monitor.y + monitor.height - height),
+        width,
+        height,
+    }
+}
+
+/// Move and resize the main window to its saved bounds, then show it. The window starts
+/// hidden (see tauri.conf.json) so the restore doesn't visibly jump.
+pub fn restore_main_window(app: &AppHandle) {
+    let window = match app.get_webview_window(MAIN_WINDOW_LABEL) {
+        Some(window) => window,
+        None => return,
+    };
+
+    if let Some(bounds) = load_config_json::<WindowBounds>(app, WINDOW_STATE_FILE) {
+        // Ignore degenerate sizes from a corrupted file rather than shrinking the window away
+        if bounds.width > 0.0 && bounds.height > 0.0 {
+            // Primary monitor first, so it's the fallback for an off-screen window
+            let primary = window.primary_monitor().ok().flatten().map(|monitor| monitor_bounds(&monitor));
+            let mut monitors: Vec<WindowBounds> = primary.into_iter().collect();
+            if let Ok(available) = window.available_monitors() {
+                monitors.extend(available.iter().map(monitor_bounds));
+            }
+
+            let bounds = clamp_to_monitors(bounds, &monitors);
+            let _ = window.set_size(LogicalSize::new(bounds.width, bounds.height));
+            let _ = window.set_position(LogicalPosition::new(bounds.x, bounds.y));
+        }
+    }
+
+    let _ = window.show();
+}
+
+/// Remember the main window's bounds for the next launch; called when it's closing
+pub fn save_main_window(window: &Window) {
+    // A minimized window reports a parked off-screen position (e.g. -32000 on Windows)
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+
+    let bounds = (|| -> tauri::Result<WindowBounds> {
+        let scale = window.scale_factor()?;
+        let position = window.outer_position()?.to_logical::<f64>(scale);
+        let size = window.inner_size()?.to_logical::<f64>(scale);
+        Ok(WindowBounds {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    })();
+
+    let result = bounds
+        .map_err(|e| e.to_string())
+        .and_then(|bounds| save_config_json(window.app_handle(), WINDOW_STATE_FILE, &bounds));
+    if let Err(e) = result {
+        eprintln!("[Window State] Failed to save window bounds: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn save_window_bounds(app: AppHandle, bounds: WindowBounds) -> Result<(), String> {
+    save_config_json(&app, WINDOW_STATE_FILE, &bounds)
+}
+
+#[tauri::command]
+pub async fn restore_window_bounds(app: AppHandle) -> Result<Option<WindowBounds>, String> {
+    Ok(load_config_json(&app, WINDOW_STATE_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> WindowBounds {
+        WindowBounds { x, y, width, height }
+    }
+
+    fn assert_rect(actual: WindowBounds, expected: WindowBounds) {
+        assert_eq!(
+            (actual.x, actual.y, actual.width, actual.height),
+            (expected.x, expected.y, expected.width, expected.height)
+        );
+    }
+
+    #[test]
+    fn clamp_to_monitors_keeps_bounds_that_fit() {
+        let monitors = [rect(0.0, 0.0, 1920.0, 1080.0)];
+        let bounds = rect(100.0, 100.0, 800.0, 600.0);
+        assert_rect(clamp_to_monitors(bounds, &monitors), bounds);
+    }
+
+    #[test]
+    fn clamp_to_monitors_moves_a_stranded_window_to_the_primary() {
+        // Saved on a second display that is no longer connected
+        let monitors = [rect(0.0, 0.0, 1440.0, 900.0)];
+        let bounds = rect(2000.0, 200.0, 800.0, 600.0);
+        assert_rect(clamp_to_monitors(bounds, &monitors), rect(640.0, 200.0, 800.0, 600.0));
+    }
+
+    #[test]
+    fn clamp_to_monitors_uses_the_monitor_with_most_overlap() {
+        let monitors = [rect(0.0, 0.0, 1920.0, 1080.0), rect(1920.0, 0.0, 1280.0, 1024.0)];
+        let bounds = rect(1800.0, 100.0, 800.0, 600.0);
+        assert_rect(clamp_to_monitors(bounds, &monitors), rect(1920.0, 100.0, 800.0, 600.0));
+    }
+
+    #[test]
+    fn clamp_to_monitors_shrinks_oversized_bounds() {
+        let monitors = [rect(0.0, 0.0, 1280.0, 800.0)];
+        let bounds = rect(-50.0, -50.0, 1600.0, 1000.0);
+        assert_rect(clamp_to_monitors(bounds, &monitors), rect(0.0, 0.0, 1280.0, 800.0));
+    }
+
+    #[test]
+    fn clamp_to_monitors_without_monitors_returns_bounds_unchanged() {
+        let bounds = rect(5000.0, 5000.0, 800.0, 600.0);
+        assert_rect(clamp_to_monitors(bounds, &[]), bounds);
+    }
+}
@@ -56,10 +56,14 @@ export function GitModal({ onClose, initialStatus, onStatusUpdate }: GitModalPro
         author_email: gitSettings.userEmail || 'user@example.com',
       };
 
-      await gitService.commit(options);
+      const result = await gitService.commit(options);
       setCommitMessage('');
       await fetchStatus();
-      showToast('Changes committed successfully', 'success');
+      if (result.warning) {
+        showToast(`Changes committed. ${result.warning}`, 'info');
+      } else {
+        showToast('Changes committed successfully', 'success');
+      }
     } catch (err) {
       const message = err instanceof Error ? err.message : String(err);
       setError(message);
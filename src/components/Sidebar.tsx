@@ -53,7 +53,7 @@ export function Sidebar() {
         const rootHandle = fileSystemService.getRootHandle();
         if (typeof rootHandle === 'string') {
           const workspacePath = `${rootHandle}/workspace`;
-          await invoke('watch_workspace', { workspacePath });
+          await invoke('watch_workspace', { workspacePath, recursive: true });
         }
       } catch (err) {
         console.error('Failed to start workspace watcher:', err);
@@ -8,9 +8,11 @@ import './Terminal.css';
 
 interface TerminalProps {
   workspacePath: string;
+  /** Stable id for this terminal, under which its note is kept across restarts */
+  terminalId?: string;
 }
 
-export function Terminal({ workspacePath }: TerminalProps) {
+export function Terminal({ workspacePath, terminalId }: TerminalProps) {
   const terminalRef = useRef<HTMLDivElement>(null);
   const xtermRef = useRef<XTerm | null>(null);
   const fitAddonRef = useRef<FitAddon | null>(null);
@@ -48,6 +50,9 @@ export function Terminal({ workspacePath }: TerminalProps) {
       try {
         const sessionId = await invoke<string>('spawn_terminal', {
           workingDir: workspacePath,
+          cols: xterm.cols,
+          rows: xterm.rows,
+          terminalId,
         });
         sessionIdRef.current = sessionId;
 
@@ -56,12 +61,25 @@ export function Terminal({ workspacePath }: TerminalProps) {
           xterm.write(event.payload);
         });
 
+        // Keep the PTY size in sync with xterm (covers fit() after window/panel resizes)
+        xterm.onResize(({ cols, rows }) => {
+          if (sessionIdRef.current) {
+            invoke('resize_terminal', {
+              sessionId: sessionIdRef.current,
+              cols,
+              rows,
+            }).catch(console.error);
+          }
+        });
+
         // Handle terminal input
+        // xterm already encodes keys (Enter is \r) and pastes correctly, so send it raw
         xterm.onData((data) => {
           if (sessionIdRef.current) {
             invoke('write_terminal', {
               sessionId: sessionIdRef.current,
               data,
+              normalizeNewlines: false,
             });
           }
         });
@@ -76,18 +94,9 @@ export function Terminal({ workspacePath }: TerminalProps) {
     const unlistenPromise = initTerminal();
 
     // Handle window resize
+    // fit() triggers xterm's onResize, which forwards the new size to the PTY
     const handleResize = () => {
       fitAddon.fit();
-      if (sessionIdRef.current) {
-        const dims = fitAddon.proposeDimensions();
-        if (dims) {
-          invoke('resize_terminal', {
-            sessionId: sessionIdRef.current,
-            cols: dims.cols,
-            rows: dims.rows,
-          });
-        }
-      }
     };
 
     window.addEventListener('resize', handleResize);
@@ -103,7 +112,7 @@ export function Terminal({ workspacePath }: TerminalProps) {
       }
       xterm.dispose();
     };
-  }, [workspacePath]);
+  }, [workspacePath, terminalId]);
 
   return (
     <div className="terminal-container">
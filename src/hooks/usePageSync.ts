@@ -226,9 +226,15 @@ export function usePageSync({ pageId, pages, onUpdate, onToast, getEditorState }
           : page.path;
 
         unlisten = await listen('file-changed', async (event: any) => {
-          const changedPath = event.payload as string;
+          const { path: changedPath, kind } = event.payload as {
+            path: string;
+            kind: 'create' | 'modify' | 'remove' | 'rename';
+          };
           if (absolutePagePath !== changedPath) return;
 
+          // Nothing to reload once the page is gone
+          if (kind === 'remove') return;
+
           // Skip if we saved recently (our own write)
           if (Date.now() - lastSaveTimeRef.current < 3000) return;
 
@@ -730,7 +730,7 @@ export function PageView() {
             </div>
           </>
         )}
-        {showTerminal && <Terminal workspacePath={config.workspacePath} />}
+        {showTerminal && <Terminal workspacePath={config.workspacePath} terminalId="page-view" />}
       </div>
 
       {/* Scroll to top button */}
@@ -8,18 +8,47 @@ import { fileSystemService } from './fileSystemFactory';
 
 export interface GitStatus {
   branch: string;
+  is_detached: boolean;
   modified_count: number;
   staged_count: number;
   ahead: number;
   behind: number;
   is_clean: boolean;
   modified_files: string[];
+  renamed_files: GitRename[];
+  last_commit: LastCommit | null;
+  has_conflicts: boolean;
+}
+
+export interface LastCommit {
+  hash: string;
+  subject: string;
+  timestamp: number;
+}
+
+export interface GitRename {
+  from: string;
+  to: string;
+}
+
+/** Payload of the `git-progress` event emitted during push, pull, clone and LFS migration */
+export interface GitProgress {
+  operation: 'push' | 'pull' | 'clone' | 'lfs-migrate';
+  message: string;
 }
 
 export interface GitCommitOptions {
   message: string;
   author_name: string;
   author_email: string;
+  allow_empty_message?: boolean;
+  amend?: boolean;
+}
+
+export interface GitCommitResult {
+  hash: string;
+  /** Non-fatal problem with the commit, e.g. a very short message */
+  warning: string | null;
 }
 
 export interface GitConfig {
@@ -84,33 +113,33 @@ export class GitService {
   /**
    * Commit changes with the given message
    */
-  async commit(options: GitCommitOptions): Promise<string> {
+  async commit(options: GitCommitOptions): Promise<GitCommitResult> {
     const workspacePath = this.getWorkspacePath();
-    return this.invokeGitCommand<string>('git_commit', { workspacePath, options });
+    return this.invokeGitCommand<GitCommitResult>('git_commit', { workspacePath, options });
   }
 
   /**
    * Push changes to remote
    */
-  async push(remoteName?: string): Promise<void> {
+  async push(remoteName?: string, timeoutSecs?: number, force?: boolean): Promise<void> {
     const workspacePath = this.getWorkspacePath();
-    return this.invokeGitCommand<void>('git_push', { workspacePath, remoteName });
+    return this.invokeGitCommand<void>('git_push', { workspacePath, remoteName, timeoutSecs, force });
   }
 
   /**
    * Pull changes from remote
    */
-  async pull(remoteName?: string): Promise<void> {
+  async pull(remoteName?: string, timeoutSecs?: number, rebase?: boolean): Promise<void> {
     const workspacePath = this.getWorkspacePath();
-    return this.invokeGitCommand<void>('git_pull', { workspacePath, remoteName });
+    return this.invokeGitCommand<void>('git_pull', { workspacePath, remoteName, timeoutSecs, rebase });
   }
 
   /**
    * Sync (pull then push)
    */
-  async sync(remoteName?: string): Promise<void> {
+  async sync(remoteName?: string, timeoutSecs?: number, rebase?: boolean): Promise<void> {
     const workspacePath = this.getWorkspacePath();
-    return this.invokeGitCommand<void>('git_sync', { workspacePath, remoteName });
+    return this.invokeGitCommand<void>('git_sync', { workspacePath, remoteName, timeoutSecs, rebase });
   }
 
   /**
@@ -4,10 +4,10 @@
  * This is the Tauri-native counterpart of FileSystemService (browser).
  */
 
+import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import {
   readTextFile,
-  writeTextFile,
   readFile as tauriReadBinaryFile,
   writeFile as tauriWriteBinaryFile,
   mkdir,
@@ -105,10 +105,15 @@ export class TauriFileSystemService implements IFileSystemService {
   }
 
   /**
-   * Write content to a file
+   * Write content to a file atomically (temp file + rename), so the file
+   * watcher never re-reads a partially written page
    */
   async writeFile(path: string, content: string): Promise<void> {
-    await writeTextFile(this.resolvePath(path), content);
+    await invoke('save_file_atomic', {
+      workspacePath: this.rootPath,
+      path: this.resolvePath(path),
+      contents: content,
+    });
   }
 
   /**